@@ -7,16 +7,32 @@ pub const COLLATERAL_VAULT_SOL_SEED: &[u8] = b"huifi-collateral-vault-sol";
 pub const VAULT_SPL_SEED: &[u8] = b"huifi-vault-spl";
 pub const VAULT_SOL_SEED: &[u8] = b"huifi-vault-sol";
 pub const TREASURY_SEED: &[u8] = b"huifi-treasury";
+pub const LIMITS_SEED: &[u8] = b"huifi-limits";
+pub const MINT_CONFIG_SEED: &[u8] = b"huifi-mint-config";
+// Prefix of the message a creator signs for an invite ticket
+pub const INVITE_TICKET_DOMAIN: &[u8] = b"huifi-invite";
+pub const MAX_TREASURY_ACCOUNTS: usize = 50;
+pub const MAX_FEE_EXEMPT_CREATORS: usize = 20;
+pub const MAX_ROLE_MEMBERS: usize = 16;
+// Hard caps that account space is sized for
+pub const MAX_POOL_MEMBERS: usize = 20;
+pub const MAX_WHITELIST_SIZE: usize = 50;
+pub const MAX_INVITE_NONCES: usize = 256;
+// Current account layout versions, bump when a layout changes and extend the migrate_* handlers
+pub const PROTOCOL_SETTINGS_VERSION: u8 = 1;
+pub const PROTOCOL_LIMITS_VERSION: u8 = 1;
+pub const MINT_CONFIG_VERSION: u8 = 1;
+pub const GROUP_ACCOUNT_VERSION: u8 = 8;
+pub const MEMBER_ACCOUNT_VERSION: u8 = 2;
+pub const BID_STATE_VERSION: u8 = 3;
+// Defaults for the on-chain ProtocolLimits account
 pub const MIN_PARTICIPANTS: u8 = 3;
 pub const MAX_PARTICIPANTS: u8 = 10;
 
-pub const MIN_CONTRIBUTION_AMOUNT: u64 = 10;
-pub const MAX_CONTRIBUTION_AMOUNT: u64 = 10000;
-
 pub const MIN_CYCLE_DURATION: u64 = 3 * 24 * 60 * 60; // 3 days in seconds
 pub const MAX_CYCLE_DURATION: u64 = 28 * 24 * 60 * 60; // 28 days in seconds
 
-pub const MIN_PAYOUT_DELAY: u64 = 1 * 24 * 60 * 60; // 1 day in seconds
+pub const MIN_PAYOUT_DELAY: u64 = 24 * 60 * 60; // 1 day in seconds
 pub const MAX_PAYOUT_DELAY: u64 = 7 * 24 * 60 * 60; // 7 days in seconds
 
 pub const DEFAULT_EARLY_WITHDRAWAL_FEE_BPS: u16 = 200; // 2%
@@ -25,9 +41,27 @@ pub const MAX_EARLY_WITHDRAWAL_FEE_BPS: u16 = 1000; // 10%
 pub const DEFAULT_COLLATERAL_REQUIREMENT_BPS: u16 = 20000; // 200%
 pub const MIN_COLLATERAL_REQUIREMENT_BPS: u16 = 13000; // 130%
 
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1000; // 10%
+pub const MAX_PENALTY_BPS: u16 = 1000; // 10%
+pub const MAX_CREATE_POOL_FEE: u64 = 1_000_000_000; // 1 SOL in lamports
+pub const MAX_MINT_CREATE_POOL_FEE: u64 = 100; // whole tokens, scaled by the mint decimals
+
+// Emergency pause flags, one bit per operation
+pub const PAUSE_CREATE_POOL: u16 = 1 << 0;
+pub const PAUSE_JOIN_POOL: u16 = 1 << 1;
+pub const PAUSE_CONTRIBUTE: u16 = 1 << 2;
+pub const PAUSE_BID: u16 = 1 << 3;
+pub const PAUSE_PAYOUT: u16 = 1 << 4;
+pub const PAUSE_SLASH: u16 = 1 << 5;
+pub const PAUSE_ALL: u16 = PAUSE_CREATE_POOL
+    | PAUSE_JOIN_POOL
+    | PAUSE_CONTRIBUTE
+    | PAUSE_BID
+    | PAUSE_PAYOUT
+    | PAUSE_SLASH;
+
 pub const BASIS_POINTS_DIVISOR: u64 = 10000; // 100% in basis points
 
 pub const MAXIMUM_AGE: u64 = 60 * 60; // 1 hour in seconds
 
-pub const BID_STATE_SEED: &[u8] = b"huifi-bid-state";
-pub const MAX_BID_AMOUNT: u64 = 1000000000000000000; // 1 SOL in lamports
\ No newline at end of file
+pub const BID_STATE_SEED: &[u8] = b"huifi-bid-state";
\ No newline at end of file
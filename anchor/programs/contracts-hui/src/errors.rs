@@ -128,7 +128,7 @@ pub enum HuiFiError {
 
     #[msg("Not a pool member")]
     NotPoolMember,
-    #[msg("Bid amount exceeds maximum allowed")]
+    #[msg("Bid exceeds the pool's maximum discount")]
     BidTooHigh,
     #[msg("Not pool winner")]
     NotPoolWinner,
@@ -158,4 +158,90 @@ pub enum HuiFiError {
     CycleNotCompleted,
     #[msg("Unauthorized access")]
     UnauthorizedAccess,
+    #[msg("Fee exceeds the allowed maximum")]
+    FeeTooHigh,
+    #[msg("No pending admin transfer")]
+    NoPendingAdmin,
+    #[msg("Invalid admin address")]
+    InvalidAdmin,
+    #[msg("Treasury still holds funds")]
+    TreasuryNotEmpty,
+    #[msg("Treasury registry is full")]
+    TreasuryRegistryFull,
+    #[msg("Treasury is still used by open pools")]
+    TreasuryInUse,
+    #[msg("Treasury has insufficient funds")]
+    InsufficientTreasuryFunds,
+    #[msg("Invalid withdraw amount")]
+    InvalidWithdrawAmount,
+    #[msg("Missing destination account")]
+    MissingDestination,
+    #[msg("Creator is already fee exempt")]
+    AlreadyFeeExempt,
+    #[msg("Creator is not fee exempt")]
+    NotFeeExempt,
+    #[msg("Fee exemption list is full")]
+    FeeExemptListFull,
+    #[msg("Token account is required for this pool")]
+    MissingTokenAccount,
+    #[msg("This operation is paused by the protocol")]
+    ProtocolPaused,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Account already holds this role")]
+    RoleAlreadyGranted,
+    #[msg("Account does not hold this role")]
+    RoleNotGranted,
+    #[msg("Role member list is full")]
+    RoleMembersFull,
+    #[msg("Invalid protocol limits")]
+    InvalidProtocolLimits,
+    #[msg("Contribution amount is outside the range allowed for this mint")]
+    ContributionOutOfRange,
+    #[msg("Invalid contribution range")]
+    InvalidContributionRange,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
+    #[msg("Whitelist exceeds the maximum size")]
+    WhitelistTooLarge,
+    #[msg("Token mint has an extension that is not supported")]
+    UnsupportedMintExtension,
+    #[msg("Token mint account is required")]
+    MissingTokenMint,
+    #[msg("Remaining accounts do not match the pool members")]
+    InvalidRemainingAccounts,
+    #[msg("Join deadline must be in the future")]
+    InvalidJoinDeadline,
+    #[msg("Join deadline has passed")]
+    JoinDeadlinePassed,
+    #[msg("Join deadline has not passed yet")]
+    JoinDeadlineNotReached,
+    #[msg("Not enough members have joined to start the pool")]
+    QuorumNotReached,
+    #[msg("A member still has collateral staked")]
+    CollateralNotWithdrawn,
+    #[msg("Wallet is already whitelisted")]
+    AlreadyWhitelisted,
+    #[msg("Wallet has already joined the pool")]
+    WhitelistedMemberJoined,
+    #[msg("Whitelist cannot be emptied, that would open the pool to everyone")]
+    WhitelistCannotBeEmptied,
+    #[msg("Invalid invite ticket")]
+    InvalidInviteTicket,
+    #[msg("Invite ticket has expired")]
+    InviteTicketExpired,
+    #[msg("Invite ticket has already been used")]
+    InviteTicketUsed,
+    #[msg("No committed bid found for this member")]
+    BidNotCommitted,
+    #[msg("Bid has already been revealed")]
+    BidAlreadyRevealed,
+    #[msg("Revealed bid does not match the commitment")]
+    InvalidBidReveal,
+    #[msg("Operation not available for this pool's payout mode")]
+    InvalidPayoutMode,
+    #[msg("SlotHashes sysvar is required to draw a lottery recipient")]
+    MissingRandomnessSource,
+    #[msg("Bid is below the pool's minimum discount")]
+    BidTooLow,
 }
\ No newline at end of file
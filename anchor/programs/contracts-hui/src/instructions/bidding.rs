@@ -2,8 +2,12 @@ use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::constants::*;
 use crate::errors::*;
+use crate::instructions::cycle_management::{award_cycle, resolve_auction};
+use anchor_lang::solana_program::sysvar;
+// Bidding is sealed: members commit to hash(amount, salt, bidder) during the Bidding phase
+// and open their commitment during the Revealing phase. Unrevealed commits are ignored.
 #[derive(Accounts)]
-pub struct SubmitBid<'info> {
+pub struct CommitBid<'info> {
     #[account(mut)]
     pub bidder: Signer<'info>,
 
@@ -17,12 +21,10 @@ pub struct SubmitBid<'info> {
     #[account(
         seeds = [POOL_SEED, group_account.uuid.as_ref()],
         bump = group_account.bump,
-        // Add validation for bid amount and check if pool is in bidding phase
         constraint = matches!(
             group_account.status,
             PoolStatus::Active { phase: CyclePhase::Bidding }
-        ) @ HuiFiError::InvalidPhase,        
-        // constraint = group_account.status == PoolStatus::Active @ HuiFiError::InvalidPoolStatus,
+        ) @ HuiFiError::InvalidPhase,
     )]
     pub group_account: Account<'info, GroupAccount>,
 
@@ -32,13 +34,24 @@ pub struct SubmitBid<'info> {
         bump = member_account.bump,
     )]
     pub member_account: Account<'info, MemberAccount>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_BID) @ HuiFiError::ProtocolPaused,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
 }
-pub fn submit_bid(ctx: Context<SubmitBid>, bid_amount: u64) -> Result<()> {
+pub fn commit_bid(ctx: Context<CommitBid>, commitment: [u8; 32]) -> Result<()> {
     let bid_state = &mut ctx.accounts.bid_state;
     let group_account = &ctx.accounts.group_account;
     let member_account = &mut ctx.accounts.member_account;
-    // Basic validations    
-    require!(bid_amount > 0, HuiFiError::InvalidBidAmount);
+
+    // Only auction pools take bids
+    require!(
+        group_account.config.payout_mode == PayoutMode::Auction,
+        HuiFiError::InvalidPayoutMode
+    );
 
     // Check if member is part of the pool
     require!(
@@ -46,66 +59,113 @@ pub fn submit_bid(ctx: Context<SubmitBid>, bid_amount: u64) -> Result<()> {
         HuiFiError::NotPoolMember
     );
 
-    // Check if member has already bid
+    // Check if member has already bid this cycle
     require!(
-        !member_account.has_bid, 
+        !member_account.has_bid_in(group_account.current_cycle), 
         HuiFiError::AlreadyBid
     );
 
+    // Each member can only take the pot once
+    require!(
+        !member_account.has_received_payout
+            && !group_account.is_past_recipient(&ctx.accounts.bidder.key()),
+        HuiFiError::AlreadyReceivedPayout
+    );
+
     bid_state.bids.push(BidEntry {
         bidder: ctx.accounts.bidder.key(),
-        amount: bid_amount,
+        amount: 0,
+        commitment,
+        revealed: false,
+        committed_at: Clock::get()?.unix_timestamp,
     });
-    // Update member account
-    member_account.has_bid = true;
-    msg!(
-        "🪙 Bid submitted: {} by {}",
-        bid_amount,
-        ctx.accounts.bidder.key()
-    );
+    member_account.last_bid_cycle = Some(group_account.current_cycle);
+    msg!("🔒 Bid committed by {}", ctx.accounts.bidder.key());
 
     Ok(())
 }
-pub fn finalize_bidding(ctx: Context<FinalizeBidding>) -> Result<()> {
-    let bid_state = &mut ctx.accounts.bid_state;
-    let group_account = &mut ctx.accounts.group_account;
 
-    // Ensure we have bids
-    require!(!bid_state.bids.is_empty(), HuiFiError::NoBids);
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BID_STATE_SEED, group_account.key().as_ref()],
+        bump = bid_state.bump
+    )]
+    pub bid_state: Account<'info, BidState>,
 
-    // Sort bids by amount (highest first)
-    bid_state.bids.sort_by(|a, b| b.amount.cmp(&a.amount));
+    #[account(
+        mut,
+        seeds = [POOL_SEED, group_account.uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = matches!(
+            group_account.status,
+            PoolStatus::Active { phase: CyclePhase::Revealing }
+        ) @ HuiFiError::InvalidPhase,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
 
-    // Get winner
-    let winner_entry = bid_state
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_BID) @ HuiFiError::ProtocolPaused,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+pub fn reveal_bid(ctx: Context<RevealBid>, bid_amount: u64, salt: [u8; 32]) -> Result<()> {
+    let bidder = ctx.accounts.bidder.key();
+    let bid = ctx.accounts.bid_state
         .bids
-        .first()
-        .cloned()
-        .ok_or(HuiFiError::NoBids)?;
+        .iter_mut()
+        .find(|bid| bid.bidder == bidder)
+        .ok_or(HuiFiError::BidNotCommitted)?;
 
-    // Update bid state
-    bid_state.winner = Some(winner_entry.bidder);
+    require!(!bid.revealed, HuiFiError::BidAlreadyRevealed);
+    require!(
+        BidEntry::commitment_for(bid_amount, &salt, &bidder) == bid.commitment,
+        HuiFiError::InvalidBidReveal
+    );
+    require!(bid_amount > 0, HuiFiError::InvalidBidAmount);
 
-    // Update group account
-    group_account.current_winner = Some(winner_entry.bidder);
-    group_account.current_bid_amount = Some(winner_entry.amount);
+    // Bids are a discount on this cycle's pot
+    let (min_bid, max_bid) = ctx.accounts.group_account.bid_bounds()?;
+    require!(bid_amount >= min_bid, HuiFiError::BidTooLow);
+    require!(bid_amount <= max_bid, HuiFiError::BidTooHigh);
 
-    // Update winner's member account
-    let member = &mut ctx.accounts.winner_member_account;
-    require!(
-        member.owner == winner_entry.bidder,
-        HuiFiError::InvalidWinnerAccount
+    bid.amount = bid_amount;
+    bid.revealed = true;
+    msg!(
+        "🪙 Bid revealed: {} by {}",
+        bid_amount,
+        bidder
     );
-    member.eligible_for_payout = true;
 
-    // // Transition to Contributing phase
-    // group_account.status = PoolStatus::Active {
-    //     phase: CyclePhase::Contributing
-    // };
+    // Nobody can outbid the maximum discount, so the first bid revealed at it closes the auction
+    if ctx.accounts.bid_state.should_finalize_bidding(max_bid) {
+        // Anything revealed at the maximum before would already have closed the auction
+        let winning_bid = resolve_auction(&ctx.accounts.group_account, &ctx.accounts.bid_state, None)?;
+        award_cycle(
+            &mut ctx.accounts.group_account,
+            &mut ctx.accounts.bid_state,
+            winning_bid.bidder,
+            winning_bid.amount,
+        )?;
+        msg!("🏁 Maximum discount reached, auction closed for {}", winning_bid.bidder);
+    }
 
-    // // Set the timestamp for the next phase
-    // let clock = Clock::get()?;
-    // group_account.last_cycle_timestamp = clock.unix_timestamp;
+    Ok(())
+}
+pub fn finalize_bidding(ctx: Context<FinalizeBidding>) -> Result<()> {
+    let bid_state = &mut ctx.accounts.bid_state;
+    let group_account = &mut ctx.accounts.group_account;
+
+    // Only revealed bids can win
+    let winner_entry = resolve_auction(group_account, bid_state, ctx.accounts.slot_hashes.as_ref())?;
+
+    // Update bid state and group account, then start contributions
+    award_cycle(group_account, bid_state, winner_entry.bidder, winner_entry.amount)?;
 
     msg!(
         "🏆 Bidding finalized for pool {} cycle {}",
@@ -124,11 +184,11 @@ pub fn finalize_bidding(ctx: Context<FinalizeBidding>) -> Result<()> {
 #[derive(Accounts)]
 pub struct FinalizeBidding<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>, // Optional: make this admin or allow any cranker
+    pub authority: Signer<'info>, // Creator only, closing the reveal window early is advance_cycle's call
 
     #[account(
         mut,
-        seeds = [BID_STATE_SEED, group_account.key().as_ref(), &[group_account.current_cycle]],
+        seeds = [BID_STATE_SEED, group_account.key().as_ref()],
         bump = bid_state.bump
     )]
     pub bid_state: Account<'info, BidState>,
@@ -137,19 +197,18 @@ pub struct FinalizeBidding<'info> {
         mut,
         seeds = [POOL_SEED, group_account.uuid.as_ref()],
         bump = group_account.bump,
+        constraint = group_account.creator == authority.key() @ HuiFiError::Unauthorized,
         // Add phase validation
         constraint = matches!(
             group_account.status,
-            PoolStatus::Active { phase: CyclePhase::Bidding }
+            PoolStatus::Active { phase: CyclePhase::Revealing }
         ) @ HuiFiError::InvalidPhase,
     )]
     pub group_account: Account<'info, GroupAccount>,
-    #[account(
-        mut,
-        seeds = [MEMBER_SEED, group_account.key().as_ref(), winner_member_account.owner.as_ref()],
-        bump = winner_member_account.bump,
-    )]
-    pub winner_member_account: Account<'info, MemberAccount>,
+
+    /// CHECK: SlotHashes sysvar, only needed to settle a Random tie
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
 }
 // ==================== NEW mark_member_eligible.rs ====================
 
@@ -180,9 +239,3 @@ pub fn mark_member_eligible(ctx: Context<MarkEligible>) -> Result<()> {
     Ok(())
 }
 
-pub fn reset_member_flags(member: &mut MemberAccount) {
-    member.eligible_for_payout = false;
-    member.has_received_payout = false;
-    // ❗ Do not reset collateral_staked here, only reset after final withdrawal
-    member.status = MemberStatus::Active;
-}
@@ -1,9 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{program::invoke, system_instruction, native_token::LAMPORTS_PER_SOL};
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::constants::*;
 use crate::state::*;
 use crate::errors::*;
+use crate::instructions::token_utils::{transfer_tokens, wrap_sol, unwrap_sol};
 use anchor_lang::system_program::{self};
 
 // ========== SOL Collateral ==========
@@ -62,7 +63,7 @@ pub fn deposit_sol_collateral(
         .saturating_div(100);
 
     require!(amount >= min_required, HuiFiError::InsufficientCollateral);
-    require!(member_account.has_deposited_collateral == false, HuiFiError::AlreadyDepositedCollateral);
+    require!(!member_account.has_deposited_collateral, HuiFiError::AlreadyDepositedCollateral);
 
     // Log amounts in SOL for better readability
     msg!("🛡️ Collateral status - Required: {} SOL (130% of total contributions), Provided: {} SOL", 
@@ -174,92 +175,210 @@ pub fn withdraw_sol_collateral(
 }
 // ========== SPL Collateral ==========
 
-// #[derive(Accounts)]
-// #[instruction(uuid: [u8; 6])]
-// pub struct DepositSplCollateral<'info> {
-//     #[account(mut)]
-//     pub user: Signer<'info>,
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct DepositSplCollateral<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
 
-//     #[account(
-//         mut,
-//         seeds = [POOL_SEED, uuid.as_ref()],
-//         bump = group_account.bump,
-//         constraint = !group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
-//         // Add phase validation - collateral can be deposited during Contributing phase
-//         constraint = matches!(
-//             group_account.status,
-//             PoolStatus::Active { phase: CyclePhase::Contributing }
-//         ) @ HuiFiError::InvalidPhase,
-//     )]
-//     pub group_account: Account<'info, GroupAccount>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = !group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
+        // Same window as SOL pools, the winner deposits before claiming
+        constraint = matches!(
+            group_account.status,
+            PoolStatus::Active { phase: CyclePhase::ReadyForPayout }
+        ) @ HuiFiError::InvalidPhase,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
 
-//     #[account(
-//         mut,
-//         seeds = [MEMBER_SEED, group_account.key().as_ref(), user.key().as_ref()],
-//         bump = member_account.bump,
-//     )]
-//     pub member_account: Account<'info, MemberAccount>,
+    #[account(
+        mut,
+        seeds = [MEMBER_SEED, group_account.key().as_ref(), user.key().as_ref()],
+        bump = member_account.bump,
+        constraint = Some(user.key()) == group_account.current_winner @ HuiFiError::NotPoolWinner,
+    )]
+    pub member_account: Account<'info, MemberAccount>,
 
-//     #[account(mut)]
-//     pub user_token_account: Account<'info, TokenAccount>,
+    // Can be omitted in wSOL pools to deposit straight from lamports
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ HuiFiError::InvalidTokenAccountOwner,
+        constraint = user_token_account.mint == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-//     #[account(
-//         mut,
-//         seeds = [COLLATERAL_VAULT_SPL_SEED, group_account.key().as_ref(), user.key().as_ref()],
-//         bump,
-//     )]
-//     pub collateral_vault_spl: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_spl: InterfaceAccount<'info, TokenAccount>,
 
-//     #[account(
-//         constraint = token_mint.key() == group_account.token_mint @ HuiFiError::InvalidTokenMint
-//     )]
-//     pub token_mint: Account<'info, Mint>,
+    #[account(
+        constraint = token_mint.key() == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
-//     pub token_program: Program<'info, Token>,
-//     pub system_program: Program<'info, System>,
-//     pub rent: Sysvar<'info, Rent>,
-// }
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
 
-// pub fn deposit_spl_collateral(
-//     ctx: Context<DepositSplCollateral>,
-//     _uuid: [u8; 6],
-//     amount: u64
-// ) -> Result<()> {
-//     let group = &ctx.accounts.group_account;
-//     let member = &mut ctx.accounts.member_account;
+pub fn deposit_spl_collateral(
+    ctx: Context<DepositSplCollateral>,
+    _uuid: [u8; 6],
+    amount: u64
+) -> Result<()> {
+    let group_account = &ctx.accounts.group_account;
+    let member_account = &mut ctx.accounts.member_account;
 
-//     let min_required = calculate_required_collateral(
-//         group.config.contribution_amount,
-//         group.config.max_participants,
-//         member.contributions_made,
-//         group.config.collateral_requirement_bps as u64,
-//     );
+    // Calculate required collateral (130% of total contributions)
+    let min_required = group_account.total_contributions
+        .saturating_sub(member_account.contributions_made.into())
+        .saturating_mul(130)
+        .saturating_div(100);
 
-//     require!(amount >= min_required, HuiFiError::InsufficientCollateral);
+    require!(!member_account.has_deposited_collateral, HuiFiError::AlreadyDepositedCollateral);
 
-//     let cpi_ctx = CpiContext::new(
-//         ctx.accounts.token_program.to_account_info(),
-//         Transfer {
-//             from: ctx.accounts.user_token_account.to_account_info(),
-//             to: ctx.accounts.collateral_vault_spl.to_account_info(),
-//             authority: ctx.accounts.user.to_account_info(),
-//         },
-//     );
+    // Only what lands in the vault counts as collateral
+    let received = match ctx.accounts.user_token_account.as_ref() {
+        Some(user_token_account) => transfer_tokens(
+            &ctx.accounts.token_program,
+            user_token_account,
+            &mut ctx.accounts.collateral_vault_spl,
+            &ctx.accounts.token_mint,
+            ctx.accounts.user.to_account_info(),
+            &[],
+            amount,
+        )?,
+        // wSOL pools wrap the member's lamports on entry
+        None => {
+            require!(group_account.is_wrapped_sol(), HuiFiError::MissingTokenAccount);
+            wrap_sol(
+                ctx.accounts.user.to_account_info(),
+                &mut ctx.accounts.collateral_vault_spl,
+                &ctx.accounts.token_program,
+                &ctx.accounts.system_program,
+                amount,
+            )?
+        }
+    };
+    require!(received >= min_required, HuiFiError::InsufficientCollateral);
+
+    msg!("🛡️ Collateral status - Required: {} (130% of total contributions), Provided: {}",
+        min_required,
+        received
+    );
 
-//     token::transfer(cpi_ctx, amount)?;
-//     member.collateral_staked += amount;
+    member_account.collateral_staked = member_account.collateral_staked
+        .checked_add(received)
+        .ok_or(HuiFiError::Overflow)?;
+    member_account.has_deposited_collateral = true;
 
-//     msg!("✅ Deposited {} SPL tokens as collateral", amount);
+    msg!("✅ Deposited {} tokens as collateral", received);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct WithdrawSplCollateral<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = !group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+
+    #[account(
+        mut,
+        seeds = [MEMBER_SEED, group_account.key().as_ref(), user.key().as_ref()],
+        bump = member_account.bump,
+    )]
+    pub member_account: Account<'info, MemberAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ HuiFiError::InvalidTokenAccountOwner,
+        constraint = user_token_account.mint == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_spl: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// `unwrap_wsol` closes user_token_account into the wallet, only for a wSOL account made for this withdrawal
+pub fn withdraw_spl_collateral(
+    ctx: Context<WithdrawSplCollateral>,
+    _uuid: [u8; 6],
+    unwrap_wsol: bool,
+) -> Result<()> {
+    let group_account = &ctx.accounts.group_account;
+    let member_account = &mut ctx.accounts.member_account;
+
+    require!(ctx.accounts.user.key() == member_account.owner, HuiFiError::UnauthorizedAccess);
+    require!(member_account.has_deposited_collateral, HuiFiError::NoCollateralDeposited);
+    require!(member_account.status != MemberStatus::Withdrawed, HuiFiError::MemberAlreadyWithdrawed);
+    require!(group_account.status == PoolStatus::Completed, HuiFiError::CycleNotCompleted);
+    require!(
+        ctx.accounts.collateral_vault_spl.amount >= member_account.collateral_staked,
+        HuiFiError::InsufficientVaultFunds
+    );
+
+    let amount = member_account.collateral_staked;
+
+    // The collateral vault is owned by the pool PDA
+    let group_seeds = &[POOL_SEED, group_account.uuid.as_ref(), &[group_account.bump]];
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        &ctx.accounts.collateral_vault_spl,
+        &mut ctx.accounts.user_token_account,
+        &ctx.accounts.token_mint,
+        group_account.to_account_info(),
+        &[group_seeds],
+        amount,
+    )?;
+    // wSOL pools unwrap on exit when asked to
+    if unwrap_wsol {
+        require!(group_account.is_wrapped_sol(), HuiFiError::InvalidPoolType);
+        unwrap_sol(
+            &ctx.accounts.user_token_account,
+            ctx.accounts.user.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+    }
+
+    member_account.collateral_staked = 0;
+    member_account.status = MemberStatus::Withdrawed;
+
+    msg!("✅ Withdrawn {} tokens collateral", amount);
+    Ok(())
+}
 
-//     Ok(())
-// }
 // ==================== SLASH COLLATERAL ====================
 
 #[derive(Accounts)]
 #[instruction(uuid: [u8; 6])]
 pub struct SlashCollateral<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>, // authority to slash (admin, pool manager)
+    pub authority: Signer<'info>, // must hold the Slasher role (or be the protocol admin)
 
     #[account(
         mut,
@@ -284,105 +403,149 @@ pub struct SlashCollateral<'info> {
     #[account(mut)]
     pub member_wallet: AccountInfo<'info>,
 
-    /// Vault holding SOL collateral (optional)
-    #[account(mut)]
-    pub collateral_vault_sol: Option<AccountInfo<'info>>,
+    /// CHECK: Native SOL collateral vault PDA (SOL pools only)
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SOL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_sol: Option<UncheckedAccount<'info>>,
 
     /// Vault holding SPL collateral (optional)
     #[account(mut)]
-    pub collateral_vault_spl: Option<Account<'info, TokenAccount>>,
+    pub collateral_vault_spl: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    // Treasury accounts
-    #[account(mut)]
-    pub treasury_usdc: Option<Account<'info, TokenAccount>>,
-    #[account(mut)]
-    pub treasury_usdt: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_SLASH) @ HuiFiError::ProtocolPaused,
+        constraint = protocol_settings.has_role(&authority.key(), Role::Slasher) @ HuiFiError::Unauthorized,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    // Treasury accounts, checked against the protocol treasury registry
     #[account(mut)]
-    pub treasury_jitosol: Option<Account<'info, TokenAccount>>,
+    pub treasury_spl: Option<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
-    pub treasury_sol: Option<AccountInfo<'info>>,
+    pub treasury_sol: Option<SystemAccount<'info>>,
 
+    // Pool vault the repayment goes back to, SOL pools pass vault_sol instead
     #[account(mut)]
-    pub pool_vault: Option<Account<'info, TokenAccount>>,
+    pub pool_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [VAULT_SOL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub vault_sol: Option<SystemAccount<'info>>,
+
+    // Pool mint (SPL pools only)
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
 
-    pub token_program: Option<Program<'info, Token>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn slash_collateral(ctx: Context<SlashCollateral>, uuid: [u8; 6], payout_amount: u64) -> Result<()> {
+pub fn slash_collateral(ctx: Context<SlashCollateral>, _uuid: [u8; 6], payout_amount: u64) -> Result<()> {
     let group = &ctx.accounts.group_account;
     let member = &mut ctx.accounts.member_account;
 
     require!(member.status == MemberStatus::Defaulted, HuiFiError::MemberNotDefaulted);
     require!(member.has_received_payout, HuiFiError::MemberNotPaidYet);
 
-    let penalty_bps = 500u64; // 5%
+    let penalty_bps = group.penalty_bps as u64;
     let penalty_amount = payout_amount
         .saturating_mul(penalty_bps)
-        .saturating_div(10_000);
+        .saturating_div(BASIS_POINTS_DIVISOR);
     let total_slash = payout_amount.saturating_add(penalty_amount);
 
     require!(total_slash <= member.collateral_staked, HuiFiError::InvalidSlashAmount);
 
-    if group.config.is_native_sol {
+    // Determine treasury
+    let treasury_key = ctx.accounts.protocol_settings
+        .treasury_for_pool(group.token_mint, group.config.is_native_sol)
+        .ok_or(HuiFiError::UnsupportedToken)?
+        .treasury;
+
+    let penalty_collected = if group.config.is_native_sol {
         let vault = ctx.accounts.collateral_vault_sol.as_ref().ok_or(HuiFiError::MissingCollateralVault)?;
         let sol_treasury = ctx.accounts.treasury_sol.as_ref().ok_or(HuiFiError::MissingTreasury)?;
-        let pool_vault = ctx.accounts.pool_vault.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
+        require!(sol_treasury.key() == treasury_key, HuiFiError::InvalidTreasuryAccount);
+        let pool_vault = ctx.accounts.vault_sol.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
 
-        // Slash SOL manually
-        **vault.lamports.borrow_mut() -= total_slash;
-        **sol_treasury.lamports.borrow_mut() += penalty_amount;
-        **pool_vault.to_account_info().lamports.borrow_mut() += payout_amount;
+        // The collateral vault is a system owned PDA, so it signs its own transfers
+        let group_key = group.key();
+        let bump = ctx.bumps.collateral_vault_sol.ok_or(HuiFiError::MissingCollateralVault)?;
+        let signer: &[&[&[u8]]] = &[&[COLLATERAL_VAULT_SOL_SEED, group_key.as_ref(), &[bump]]];
+        let system_program = ctx.accounts.system_program.to_account_info();
+        for (to, amount) in [(pool_vault.to_account_info(), payout_amount), (sol_treasury.to_account_info(), penalty_amount)] {
+            if amount > 0 {
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        system_program.clone(),
+                        system_program::Transfer { from: vault.to_account_info(), to },
+                        signer,
+                    ),
+                    amount,
+                )?;
+            }
+        }
 
         msg!("🛡️ Slashed SOL collateral: {} to pool, {} penalty to treasury", payout_amount, penalty_amount);
+        penalty_amount
     } else {
         let vault = ctx.accounts.collateral_vault_spl.as_ref().ok_or(HuiFiError::MissingCollateralVault)?;
-        let pool_vault = ctx.accounts.pool_vault.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
-        let token_program = ctx.accounts.token_program.as_ref().ok_or(HuiFiError::MissingTokenProgram)?.to_account_info();
+        let pool_vault = ctx.accounts.pool_vault.as_mut().ok_or(HuiFiError::MissingPoolVault)?;
+        let token_program = ctx.accounts.token_program.as_ref().ok_or(HuiFiError::MissingTokenProgram)?;
+        let token_mint = ctx.accounts.token_mint.as_ref().ok_or(HuiFiError::MissingTokenMint)?;
+        require!(token_mint.key() == group.token_mint, HuiFiError::InvalidTokenMint);
         let group_key = group.key();
-        let member_key = member.owner;
-        let seeds = &[
-            COLLATERAL_VAULT_SPL_SEED,
-            group_key.as_ref(),
-            member_key.as_ref(),
-        ];
+        let (collateral_vault_key, _) = Pubkey::find_program_address(
+            &[COLLATERAL_VAULT_SPL_SEED, group_key.as_ref()],
+            ctx.program_id,
+        );
+        require!(vault.key() == collateral_vault_key, HuiFiError::InvalidVault);
+        require!(pool_vault.key() == group.vault, HuiFiError::InvalidVault);
+
+        // Both vaults are owned by the pool PDA
+        let seeds = &[POOL_SEED, group.uuid.as_ref(), &[group.bump]];
         let signer = &[&seeds[..]];
 
-        // Determine treasury
-        let treasury_target = match group.token_mint {
-            m if ctx.accounts.treasury_usdc.as_ref().map(|a| a.mint) == Some(m) => ctx.accounts.treasury_usdc.as_ref().unwrap().to_account_info(),
-            m if ctx.accounts.treasury_usdt.as_ref().map(|a| a.mint) == Some(m) => ctx.accounts.treasury_usdt.as_ref().unwrap().to_account_info(),
-            m if ctx.accounts.treasury_jitosol.as_ref().map(|a| a.mint) == Some(m) => ctx.accounts.treasury_jitosol.as_ref().unwrap().to_account_info(),
-            _ => return Err(HuiFiError::UnsupportedToken.into()),
-        };
+        let treasury = ctx.accounts.treasury_spl.as_mut().ok_or(HuiFiError::MissingTreasury)?;
+        require!(treasury.key() == treasury_key, HuiFiError::InvalidTreasuryAccount);
 
         // Transfer repayment
-        let repay_ctx = CpiContext::new_with_signer(
-            token_program.clone(),
-            Transfer {
-                from: vault.to_account_info(),
-                to: pool_vault.to_account_info(),
-                authority: group.to_account_info(),
-            },
+        transfer_tokens(
+            token_program,
+            vault,
+            pool_vault,
+            token_mint,
+            group.to_account_info(),
             signer,
-        );
-        token::transfer(repay_ctx, payout_amount)?;
+            payout_amount,
+        )?;
 
-        // Transfer penalty
-        let penalty_ctx = CpiContext::new_with_signer(
+        // Transfer penalty, the treasury records what it actually received
+        let received = transfer_tokens(
             token_program,
-            Transfer {
-                from: vault.to_account_info(),
-                to: treasury_target,
-                authority: group.to_account_info(),
-            },
+            vault,
+            treasury,
+            token_mint,
+            group.to_account_info(),
             signer,
-        );
-        token::transfer(penalty_ctx, penalty_amount)?;
+            penalty_amount,
+        )?;
 
-        msg!("🛡️ Slashed SPL collateral: {} to pool, {} penalty to treasury", payout_amount, penalty_amount);
-    }
+        msg!("🛡️ Slashed SPL collateral: {} to pool, {} penalty to treasury", payout_amount, received);
+        received
+    };
 
+    ctx.accounts.protocol_settings.record_fee(
+        group.token_mint,
+        group.config.is_native_sol,
+        penalty_collected,
+    )?;
     member.collateral_staked = member.collateral_staked.saturating_sub(total_slash);
 
     Ok(())
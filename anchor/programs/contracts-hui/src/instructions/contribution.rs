@@ -3,11 +3,11 @@ use {
         prelude::*,
         solana_program::{program::invoke,native_token::LAMPORTS_PER_SOL,system_instruction},
     },
-    anchor_spl::token::{self, Token, TokenAccount, Transfer},
-    pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2},
+    anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface},
     crate::state::*,
     crate::constants::*,
     crate::errors::*,
+    crate::instructions::token_utils::{transfer_tokens, wrap_sol},
 };
 
 // ==================== CONTRIBUTE FUNCTIONS ====================
@@ -22,12 +22,12 @@ pub struct ContributeSol<'info> {
         mut,
         seeds = [POOL_SEED, uuid.as_ref()],
         bump = group_account.bump,
+        constraint = group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
                 // Modified constraint to check specific phase
         constraint = matches!(
             group_account.status,
             PoolStatus::Active { phase: CyclePhase::Contributing }
         ) @ HuiFiError::InvalidPhase,
-        // constraint = group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
     )]
     pub group_account: Account<'info, GroupAccount>,
     
@@ -48,6 +48,13 @@ pub struct ContributeSol<'info> {
         bump,
     )]
     pub vault_sol: AccountInfo<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_CONTRIBUTE) @ HuiFiError::ProtocolPaused,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
     ///CHECK: This is a PDA that holds the price update
     // pub price_update: Account<'info, PriceUpdateV2>,
     pub system_program: Program<'info, System>,
@@ -64,7 +71,7 @@ pub fn contribute_sol(ctx: Context<ContributeSol>, uuid: [u8; 6], amount: u64) -
         HuiFiError::InvalidPoolUUID
     );    
     require!(
-        !member_account.has_contributed,  // must be false to proceed
+        !member_account.has_contributed_in(group_account.current_cycle),  // must be false to proceed
         HuiFiError::HasAlreadyContributed
     );
     require!(
@@ -131,9 +138,10 @@ pub fn contribute_sol(ctx: Context<ContributeSol>, uuid: [u8; 6], amount: u64) -
     member_account.contributions_made = member_account.contributions_made.saturating_add(1);
     member_account.total_contributions = member_account.total_contributions.saturating_add(amount);
     member_account.last_contribution_timestamp = current_timestamp;
-    member_account.has_contributed = true;
+    member_account.last_contribution_cycle = Some(group_account.current_cycle);
     // Update pool account
     group_account.total_contributions = group_account.total_contributions.saturating_add(amount);
+    group_account.cycle_contributors = group_account.cycle_contributors.saturating_add(1);
     
     msg!("Contribution of {} SOL received from {}", 
         amount as f64 / LAMPORTS_PER_SOL as f64,  // Convert lamports to SOL for display
@@ -153,98 +161,132 @@ pub fn contribute_sol(ctx: Context<ContributeSol>, uuid: [u8; 6], amount: u64) -
     Ok(())
 }
 
-// #[derive(Accounts)]
-// #[instruction(uuid: [u8; 6], amount: u64)]
-// pub struct ContributeSpl<'info> {
-//     #[account(mut)]
-//     pub contributor: Signer<'info>,
-    
-//     #[account(
-//         mut,
-//         seeds = [POOL_SEED, uuid.as_ref()],
-//         bump = group_account.bump,
-//         constraint = group_account.status == PoolStatus::Active @ HuiFiError::InvalidPoolStatus,
-//         constraint = !group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
-//     )]
-//     pub group_account: Account<'info, GroupAccount>,
-    
-//     #[account(
-//         mut,
-//         seeds = [MEMBER_SEED, group_account.key().as_ref(), contributor.key().as_ref()],
-//         bump = member_account.bump,
-//         constraint = member_account.owner == contributor.key() @ HuiFiError::Unauthorized,
-//         constraint = member_account.pool == group_account.key() @ HuiFiError::MemberNotFound,
-//         constraint = member_account.contributions_made < group_account.current_cycle + 1 @ HuiFiError::AlreadyContributed,
-//     )]
-//     pub member_account: Account<'info, MemberAccount>,
-    
-//     #[account(
-//         mut,
-//         constraint = contributor_token_account.owner == contributor.key() @ HuiFiError::InvalidTokenAccountOwner,
-//         constraint = contributor_token_account.mint == group_account.token_mint @ HuiFiError::InvalidPoolConfig,
-//     )]
-//     pub contributor_token_account: Account<'info, TokenAccount>,
-    
-//     #[account(
-//         mut,
-//         seeds = [VAULT_SPL_SEED, group_account.key().as_ref()],
-//         bump,
-//     )]
-//     pub vault_spl: Account<'info, TokenAccount>,
-    
-//     pub token_program: Program<'info, Token>,
-//     pub system_program: Program<'info, System>,
-// }
-
-// pub fn contribute_spl(ctx: Context<ContributeSpl>, uuid: [u8; 6], amount: u64) -> Result<()> {
-//     let group_account = &mut ctx.accounts.group_account;
-//     let member_account = &mut ctx.accounts.member_account;
-//     let discount = if Some(ctx.accounts.contributor.key()) == group_account.current_winner {
-//         group_account.current_bid_amount.unwrap_or(0)
-//     } else {
-//         0
-//     };
-//     // 🛡️ Enforce UUID matches
-//     require!(
-//         group_account.uuid == uuid,
-//         HuiFiError::InvalidPoolUUID
-//     );    
-//     let required_contribution = group_account
-//         .config
-//         .contribution_amount
-//         .saturating_sub(discount);
-//     // Validate the contribution amount
-//     require!(
-//         amount == required_contribution,
-//         HuiFiError::InsufficientContribution
-//     );
-
-    
-//     let current_timestamp = Clock::get()?.unix_timestamp;
-    
-//     // Transfer SPL tokens from contributor to pool vault
-//     let cpi_accounts = Transfer {
-//         from: ctx.accounts.contributor_token_account.to_account_info(),
-//         to: ctx.accounts.vault_spl.to_account_info(),
-//         authority: ctx.accounts.contributor.to_account_info(),
-//     };
-    
-//     let cpi_program = ctx.accounts.token_program.to_account_info();
-//     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    
-//     token::transfer(cpi_ctx, amount)?;
-    
-//     // Update member account
-//     member_account.contributions_made = member_account.contributions_made.saturating_add(1);
-//     member_account.last_contribution_timestamp = current_timestamp;
-    
-//     // Update pool account
-//     group_account.total_contributions = group_account.total_contributions.saturating_add(amount);
-    
-//     msg!("Contribution of {} tokens received from {}", 
-//         amount, 
-//         ctx.accounts.contributor.key()
-//     );
-    
-//     Ok(())
-// }
\ No newline at end of file
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6], amount: u64)]
+pub struct ContributeSpl<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = matches!(
+            group_account.status,
+            PoolStatus::Active { phase: CyclePhase::Contributing }
+        ) @ HuiFiError::InvalidPhase,
+        constraint = !group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+    
+    #[account(
+        mut,
+        seeds = [MEMBER_SEED, group_account.key().as_ref(), contributor.key().as_ref()],
+        bump = member_account.bump,
+        constraint = member_account.owner == contributor.key() @ HuiFiError::Unauthorized,
+        constraint = member_account.pool == group_account.key() @ HuiFiError::MemberNotFound,
+    )]
+    pub member_account: Account<'info, MemberAccount>,
+    
+    // Can be omitted in wSOL pools to contribute straight from lamports
+    #[account(
+        mut,
+        constraint = contributor_token_account.owner == contributor.key() @ HuiFiError::InvalidTokenAccountOwner,
+        constraint = contributor_token_account.mint == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub contributor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub vault_spl: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_CONTRIBUTE) @ HuiFiError::ProtocolPaused,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn contribute_spl(ctx: Context<ContributeSpl>, uuid: [u8; 6], amount: u64) -> Result<()> {
+    let group_account = &mut ctx.accounts.group_account;
+    let member_account = &mut ctx.accounts.member_account;
+
+    // 🛡️ Enforce UUID matches
+    require!(
+        group_account.uuid == uuid,
+        HuiFiError::InvalidPoolUUID
+    );
+    require!(
+        !member_account.has_contributed_in(group_account.current_cycle),
+        HuiFiError::HasAlreadyContributed
+    );
+    require!(
+        member_account.status != MemberStatus::Defaulted,
+        HuiFiError::MemberHadDefaulted
+    );
+
+    // Same rule as SOL pools, amounts are in the mint's base units
+    let required_contribution = group_account.final_contribution_amount.unwrap_or(0);
+    require!(
+        amount == required_contribution,
+        HuiFiError::InsufficientContribution
+    );
+
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    
+    // Transfer SPL tokens from contributor to pool vault, net of any transfer fee
+    let received = match ctx.accounts.contributor_token_account.as_ref() {
+        Some(contributor_token_account) => transfer_tokens(
+            &ctx.accounts.token_program,
+            contributor_token_account,
+            &mut ctx.accounts.vault_spl,
+            &ctx.accounts.token_mint,
+            ctx.accounts.contributor.to_account_info(),
+            &[],
+            amount,
+        )?,
+        // wSOL pools wrap the contributor's lamports on entry
+        None => {
+            require!(group_account.is_wrapped_sol(), HuiFiError::MissingTokenAccount);
+            wrap_sol(
+                ctx.accounts.contributor.to_account_info(),
+                &mut ctx.accounts.vault_spl,
+                &ctx.accounts.token_program,
+                &ctx.accounts.system_program,
+                amount,
+            )?
+        }
+    };
+    
+    // Update member account
+    member_account.contributions_made = member_account.contributions_made.saturating_add(1);
+    member_account.total_contributions = member_account.total_contributions.saturating_add(received);
+    member_account.last_contribution_timestamp = current_timestamp;
+    member_account.last_contribution_cycle = Some(group_account.current_cycle);
+    // Update pool account
+    group_account.total_contributions = group_account.total_contributions.saturating_add(received);
+    group_account.cycle_contributors = group_account.cycle_contributors.saturating_add(1);
+    
+    msg!("💰 Contribution received: {} tokens from {} (Winner discount: {})", 
+        received,
+        ctx.accounts.contributor.key(),
+        group_account.current_bid_amount.unwrap_or(0)
+    );
+
+    if group_account.all_members_contributed() {
+        msg!("✅ All members have contributed for cycle {}", group_account.current_cycle);
+    }
+    Ok(())
+}
\ No newline at end of file
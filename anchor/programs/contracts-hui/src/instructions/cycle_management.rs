@@ -2,8 +2,10 @@ use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::*;
 use crate::constants::*;
-
-const POOL_STATUS_ACTIVE: u8 = 1;
+use anchor_lang::solana_program::{hash::hashv, sysvar};
+use crate::instructions::token_utils::{transfer_tokens, close_token_account};
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct AdvanceCycle<'info> {
@@ -21,18 +23,14 @@ pub struct AdvanceCycle<'info> {
 
     #[account(
         mut,
-        seeds = [b"bid", group_account.key().as_ref(), &[group_account.current_cycle]],
-        bump
+        seeds = [BID_STATE_SEED, group_account.key().as_ref()],
+        bump = bid_state.bump
     )]
     pub bid_state: Account<'info, BidState>,
 
-    // Optional: Include winner's member account for eligibility update
-    #[account(
-        mut,
-        seeds = [MEMBER_SEED, group_account.key().as_ref(), authority.owner.as_ref()],
-        bump = winner_member_account.bump,
-    )]
-    pub winner_member_account: Option<Account<'info, MemberAccount>>,
+    /// CHECK: SlotHashes sysvar, only needed to draw a Lottery recipient
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
 }
 
 pub fn advance_cycle(ctx: Context<AdvanceCycle>) -> Result<()> {
@@ -42,40 +40,45 @@ pub fn advance_cycle(ctx: Context<AdvanceCycle>) -> Result<()> {
     let current_timestamp = clock.unix_timestamp;
 
     match group_account.get_current_phase() {
+        Some(CyclePhase::Bidding) if group_account.config.payout_mode != PayoutMode::Auction || group_account.is_final_cycle() => {
+            msg!("🎯 Selecting cycle recipient");
+
+            let recipient = select_recipient(group_account, ctx.accounts.slot_hashes.as_ref())?;
+            award_cycle(group_account, bid_state, recipient, 0)?;
+
+            msg!("🏆 Recipient selected: {}", recipient);
+            msg!("➡️ Entering Contributing phase");
+        },
         Some(CyclePhase::Bidding) => {
-            msg!("📊 Finalizing bidding phase");
+            msg!("🔒 Closing commit phase");
             
             // Verify we have bids
             require!(!bid_state.bids.is_empty(), HuiFiError::NoBids);
 
-            // Sort bids and get winner outside of the borrow
-            bid_state.bids.sort_by(|a, b| b.amount.cmp(&a.amount));
-            let winning_bid = bid_state.bids.first().cloned()
-                .ok_or(HuiFiError::NoBids)?;
-            
-            // Update bid state
-            bid_state.winner = Some(winning_bid.bidder);
-            
-            // Update group account
-            group_account.current_winner = Some(winning_bid.bidder);
-            group_account.current_bid_amount = Some(winning_bid.amount);
-
-            // Update winner eligibility if account provided
-            if let Some(winner_account) = &mut ctx.accounts.winner_member_account {
-                require!(
-                    winner_account.owner == winning_bid.bidder,
-                    HuiFiError::InvalidWinnerAccount
-                );
-                winner_account.eligible_for_payout = true;
-            }
-
-            // Transition to Contributing phase
+            // Transition to Revealing phase
             group_account.status = PoolStatus::Active {
-                phase: CyclePhase::Contributing
+                phase: CyclePhase::Revealing
+            };
+
+            msg!("➡️ Entering Revealing phase");
+        },
+        Some(CyclePhase::Revealing) => {
+            msg!("📊 Finalizing bidding phase");
+
+            // Only revealed bids can win, without any the next member in payout order is paid
+            let (winner, amount) = if bid_state.revealed_bids().next().is_some() {
+                let winning_bid = resolve_auction(group_account, bid_state, ctx.accounts.slot_hashes.as_ref())?;
+                (winning_bid.bidder, winning_bid.amount)
+            } else {
+                msg!("ℹ️ No revealed bids in this cycle, next member in payout order wins");
+                (select_recipient(group_account, None)?, 0)
             };
+            
+            // Update bid state and group account, then start contributions
+            award_cycle(group_account, bid_state, winner, amount)?;
 
-            msg!("🏆 Winner selected: {}", winning_bid.bidder);
-            msg!("💰 Winning bid amount: {}", winning_bid.amount);
+            msg!("🏆 Winner selected: {}", winner);
+            msg!("💰 Winning bid amount: {}", amount);
             msg!("➡️ Entering Contributing phase");
             // msg!("🔄 Cycle {} Phase: {:?}", group_account.current_cycle, CyclePhase::Contributing);
         },
@@ -94,11 +97,17 @@ pub fn advance_cycle(ctx: Context<AdvanceCycle>) -> Result<()> {
             };
             group_account.next_payout_timestamp = current_timestamp + 
                 group_account.config.payout_delay_seconds as i64;
+            // The pot stays unclaimed until the recipient processes the payout
+            group_account.unclaimed_payout = group_account.total_contributions;
 
             msg!("➡️ Entering Payout phase");
         },
         Some(CyclePhase::ReadyForPayout) => {
             msg!("💫 Completing current cycle");
+            require!(
+                group_account.unclaimed_payout == 0,
+                HuiFiError::UnclaimedPayouts
+            );
 
             if group_account.current_cycle + 1 >= group_account.total_cycles {
                 group_account.status = PoolStatus::Completed;
@@ -111,6 +120,7 @@ pub fn advance_cycle(ctx: Context<AdvanceCycle>) -> Result<()> {
                 group_account.current_winner = None;
                 group_account.current_bid_amount = None;
                 group_account.last_cycle_timestamp = current_timestamp;
+                group_account.cycle_contributors = 0;
                 
                 // Clear bid state
                 bid_state.bids.clear();
@@ -122,6 +132,13 @@ pub fn advance_cycle(ctx: Context<AdvanceCycle>) -> Result<()> {
                 };
 
                 msg!("➡️ Advanced to cycle {} - Bidding phase", group_account.current_cycle);
+
+                // The last member still waiting takes the final pot without bidding
+                if group_account.is_final_cycle() {
+                    let recipient = select_recipient(group_account, None)?;
+                    award_cycle(group_account, bid_state, recipient, 0)?;
+                    msg!("🏁 Final cycle awarded to {}", recipient);
+                }
             }
         },
         _ => return Err(HuiFiError::InvalidPoolStatus.into()),
@@ -133,11 +150,89 @@ pub fn advance_cycle(ctx: Context<AdvanceCycle>) -> Result<()> {
     Ok(())
 }
 
-// Optional: Helper function to verify contributions
-fn verify_all_contributions(group_account: &GroupAccount) -> bool {
-    // Implement contribution verification logic
-    // For MVP, you might want to skip this or implement a simple version
-    true
+// Pick this cycle's recipient when nobody won it in an auction.
+// payout_order[..current_cycle] holds past recipients, so a draw only ever
+// lands on members still waiting and is swapped into this cycle's slot.
+fn select_recipient(
+    group_account: &mut Account<GroupAccount>,
+    slot_hashes: Option<&UncheckedAccount>,
+) -> Result<Pubkey> {
+    let cycle = group_account.current_cycle as usize;
+    require!(cycle < group_account.payout_order.len(), HuiFiError::InvalidPoolStatus);
+
+    let waiting = (group_account.payout_order.len() - cycle) as u64;
+    if group_account.config.payout_mode == PayoutMode::Lottery && waiting > 1 {
+        let seed = slot_hash_seed(slot_hashes, group_account, b"lottery")?;
+        let draw = seed % waiting;
+        group_account.payout_order.swap(cycle, cycle + draw as usize);
+    }
+    Ok(group_account.payout_order[cycle])
+}
+
+// Seed from the most recent SlotHashes entry, mixed with the pool, cycle and a per-use tag
+fn slot_hash_seed(
+    slot_hashes: Option<&UncheckedAccount>,
+    group_account: &Account<GroupAccount>,
+    tag: &[u8],
+) -> Result<u64> {
+    // SlotHashes can be influenced by the leader and the cranker, fine for testing only
+    let slot_hashes = slot_hashes.ok_or(HuiFiError::MissingRandomnessSource)?;
+    let data = slot_hashes.try_borrow_data()?;
+    // Skip the entry count and the slot of the most recent entry
+    let recent_hash = data.get(16..48).ok_or(HuiFiError::MissingRandomnessSource)?;
+    let seed = hashv(&[recent_hash, group_account.key().as_ref(), &[group_account.current_cycle], tag]).to_bytes();
+    Ok(u64::from_le_bytes(seed[..8].try_into().unwrap()))
+}
+
+// Pick the auction winner under the pool's tie-break rule and log how it was settled
+pub(crate) fn resolve_auction(
+    group_account: &Account<GroupAccount>,
+    bid_state: &BidState,
+    slot_hashes: Option<&UncheckedAccount>,
+) -> Result<BidEntry> {
+    let rule = group_account.config.tie_break_rule;
+    let top_amount = bid_state.get_winning_bid_amount().ok_or(HuiFiError::NoBids)?;
+    // Only draw when there is a tie to settle
+    let is_tied = bid_state.revealed_bids().filter(|bid| bid.amount == top_amount).count() > 1;
+    let random_seed = if rule == TieBreakRule::Random && is_tied {
+        Some(slot_hash_seed(slot_hashes, group_account, b"tie-break")?)
+    } else {
+        None
+    };
+
+    let (winner, tied_bids) = bid_state
+        .resolve_winner(rule, &group_account.member_addresses, random_seed)?
+        .ok_or(HuiFiError::NoBids)?;
+    if tied_bids > 1 {
+        msg!("⚖️ {} bids tied at {}, settled for {}", tied_bids, winner.amount, winner.bidder);
+    }
+
+    emit!(BiddingFinalized {
+        pool: group_account.key(),
+        cycle: group_account.current_cycle,
+        winner: winner.bidder,
+        amount: winner.amount,
+        tied_bids,
+        tie_break_rule: rule,
+    });
+    Ok(winner)
+}
+
+// Award this cycle's pot, less the winning bid, and move on to contributions.
+// Payouts are gated on current_winner, so no member account is needed here.
+pub(crate) fn award_cycle(
+    group_account: &mut Account<GroupAccount>,
+    bid_state: &mut Account<BidState>,
+    recipient: Pubkey,
+    bid_amount: u64,
+) -> Result<()> {
+    bid_state.winner = Some(recipient);
+    group_account.set_winning_bid(recipient, bid_amount)?;
+
+    group_account.status = PoolStatus::Active {
+        phase: CyclePhase::Contributing
+    };
+    Ok(())
 }
 
 // Optional: Add a function to check current cycle status
@@ -192,11 +287,15 @@ pub fn check_cycle_status(ctx: Context<CheckCycleStatus>) -> Result<()> {
 //=============== FOCRCE ADVANCE CYCLE FUNCTIONS ====================
 #[derive(Accounts)]
 pub struct ForceAdvanceCycle<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
     #[account(
-        mut,
-        // constraint = signer.key() == group_account.creator @ HuiFiError::Unauthorized // Enforce creator check
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::Operator) @ HuiFiError::Unauthorized,
     )]
-    pub authority: Signer<'info>,
+    pub protocol_settings: Account<'info, ProtocolSettings>,
     
     #[account(
         mut,
@@ -205,24 +304,16 @@ pub struct ForceAdvanceCycle<'info> {
     )]
     pub group_account: Account<'info, GroupAccount>,
     
-    #[account(
-        seeds = [BID_STATE_SEED,
-            group_account.key().as_ref()],
-        bump,
-    )]
-    pub bid_state: Account<'info, BidState>, 
-    // Optional winner member account
     #[account(
         mut,
-        seeds = [
-            MEMBER_SEED, 
-            group_account.key().as_ref(), 
-            // bid_state.winner.unwrap_or_default().as_ref()
-            bid_state.winner.unwrap_or(group_account.creator).as_ref()
-        ],
-        bump,
+        seeds = [BID_STATE_SEED, group_account.key().as_ref()],
+        bump = bid_state.bump,
     )]
-    pub winner_member_account: Option<Account<'info, MemberAccount>>,
+    pub bid_state: Account<'info, BidState>,
+
+    /// CHECK: SlotHashes sysvar, only needed to draw a Lottery recipient
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
 }
 
 pub fn force_advance_cycle(ctx: Context<ForceAdvanceCycle>) -> Result<()> {
@@ -243,22 +334,34 @@ pub fn force_advance_cycle(ctx: Context<ForceAdvanceCycle>) -> Result<()> {
             msg!("➡️ Forced to Active - Bidding phase");
         },
         PoolStatus::Active { phase } => match phase {
+            CyclePhase::Bidding if group_account.config.payout_mode != PayoutMode::Auction || group_account.is_final_cycle() => {
+                msg!("📊 Force selecting cycle recipient");
+                let recipient = select_recipient(group_account, ctx.accounts.slot_hashes.as_ref())?;
+                award_cycle(group_account, bid_state, recipient, 0)?;
+                msg!("➡️ Forced to Contributing phase");
+            }
             CyclePhase::Bidding => {
-                msg!("📊 Force advancing from bidding phase");
+                msg!("📊 Force advancing from commit phase");
+                group_account.status = PoolStatus::Active {
+                    phase: CyclePhase::Revealing
+                };
+                msg!("➡️ Forced to Revealing phase");
+            }
+            CyclePhase::Revealing => {
+                msg!("📊 Force advancing from reveal phase");
                 
-                let (winning_bid, message) = if bid_state.bids.is_empty() {
-                    // If no bids, creator wins with 0 bid
+                let revealed_count = bid_state.revealed_bids().count();
+                let (winning_bid, message) = if revealed_count == 0 {
+                    // If no revealed bids, the next member in payout order wins with 0 bid
                     (
-                        (group_account.creator, 0),
-                        "ℹ️ No bids in this cycle, Creator wins"
+                        (select_recipient(group_account, None)?, 0),
+                        "ℹ️ No revealed bids in this cycle, next member in payout order wins"
                     )
                 } else {
-                    // Sort bids for both single and multiple bid cases
-                    bid_state.bids.sort_by(|a, b| b.amount.cmp(&a.amount));
-                    let top_bid = bid_state.bids.first().cloned().unwrap();
+                    let top_bid = resolve_auction(group_account, bid_state, ctx.accounts.slot_hashes.as_ref())?;
                     (
                         (top_bid.bidder, top_bid.amount),
-                        if bid_state.bids.len() == 1 {
+                        if revealed_count == 1 {
                             "🏆 Single bidder wins automatically"
                         } else {
                             "🏆 Highest bidder wins"
@@ -266,50 +369,31 @@ pub fn force_advance_cycle(ctx: Context<ForceAdvanceCycle>) -> Result<()> {
                     )
                 };
             
-                // Update bid state and group account
-                bid_state.winner = Some(winning_bid.0);
-                group_account.current_winner = Some(winning_bid.0);
-                group_account.current_bid_amount = Some(winning_bid.1);
-                group_account.final_contribution_amount = Some(
-                    group_account.config.contribution_amount - winning_bid.1
-                );
-            
-                // Set winner eligible for payout if not creator
-                if !bid_state.bids.is_empty() {
-                    if let Some(winner_account) = &mut ctx.accounts.winner_member_account {
-                        winner_account.eligible_for_payout = true;
-                    }
-                }
-            
+                // Update bid state and group account, then start contributions
+                award_cycle(group_account, bid_state, winning_bid.0, winning_bid.1)?;
                 msg!("{}", message);
-            
-                // Advance to Contributing phase
-                group_account.status = PoolStatus::Active {
-                    phase: CyclePhase::Contributing
-                };
                 msg!("➡️ Forced to Contributing phase");
             }
             CyclePhase::Contributing => {
                 msg!("💫 Force advancing from contribution phase");
-                if let Some(winner_account) = &mut ctx.accounts.winner_member_account {
-                    winner_account.payout_amount = group_account.total_contributions;
-                }
                 group_account.status = PoolStatus::Active {
                     phase: CyclePhase::ReadyForPayout
                 };
                 group_account.next_payout_timestamp = current_timestamp;
+                group_account.unclaimed_payout = group_account.total_contributions;
                 msg!("➡️ Forced to Payout phase");
             },
             CyclePhase::ReadyForPayout => {
                 msg!("💫 Force completing current cycle");
 
-                if group_account.current_cycle >= group_account.total_cycles {
+                if group_account.current_cycle + 1 >= group_account.total_cycles {
                     group_account.status = PoolStatus::Completed;
                     msg!("✅ Pool completed! All cycles finished.");
                 } else {              
                     group_account.current_winner = None;
                     group_account.current_bid_amount = None;
                     group_account.last_cycle_timestamp = current_timestamp;
+                    group_account.cycle_contributors = 0;
 
                     bid_state.bids = Vec::new();
                     bid_state.winner = None;
@@ -318,6 +402,13 @@ pub fn force_advance_cycle(ctx: Context<ForceAdvanceCycle>) -> Result<()> {
                         phase: CyclePhase::Bidding
                     };
                     msg!("➡️ Forced to cycle {} - Bidding phase", group_account.current_cycle);
+
+                    // The last member still waiting takes the final pot without bidding
+                    if group_account.is_final_cycle() {
+                        let recipient = select_recipient(group_account, None)?;
+                        award_cycle(group_account, bid_state, recipient, 0)?;
+                        msg!("🏁 Final cycle awarded to {}", recipient);
+                    }
                 }
             },
         },
@@ -333,7 +424,15 @@ pub fn force_advance_cycle(ctx: Context<ForceAdvanceCycle>) -> Result<()> {
 
 }
 
+//=============== CLOSE POOL FUNCTIONS ====================
+//
+// Closing a completed pool:
+// - refused while any member has collateral staked or a payout they have not claimed
+// - every member account is closed, its rent goes back to that member
+// - whatever is left in the vaults is dust and goes to the protocol treasury
+// - vault rent, the bid state and the group account go back to the creator
 #[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
 pub struct ClosePool<'info> {
     #[account(
         mut,
@@ -343,6 +442,8 @@ pub struct ClosePool<'info> {
     
     #[account(
         mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
         constraint = group_account.status == PoolStatus::Completed @ HuiFiError::InvalidPoolStatus,
         close = signer
     )]
@@ -351,51 +452,231 @@ pub struct ClosePool<'info> {
     #[account(
         mut,
         seeds = [BID_STATE_SEED, group_account.key().as_ref()],
-        bump,
+        bump = bid_state.bump,
         close = signer
     )]
     pub bid_state: Account<'info, BidState>,
 
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    // SOL pools pass the SOL vaults and treasury
     /// CHECK: This is a PDA that holds SOL
     #[account(
         mut,
         seeds = [VAULT_SOL_SEED, group_account.key().as_ref()],
         bump,
     )]
-    pub vault_sol: UncheckedAccount<'info>,
+    pub vault_sol: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: This is a PDA that holds SOL collateral
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SOL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_sol: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Protocol SOL treasury that receives vault dust
+    #[account(
+        mut,
+        owner = system_program.key(),
+        seeds = [TREASURY_SEED, b"sol"],
+        bump,
+    )]
+    pub treasury_sol: Option<UncheckedAccount<'info>>,
 
+    // SPL pools pass the token vaults and the mint's treasury
+    #[account(
+        mut,
+        seeds = [VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub vault_spl: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_spl: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Treasury for the pool's mint, checked against the registry
+    #[account(mut)]
+    pub protocol_treasury: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Writable so withheld Token-2022 fees can be harvested before the vaults close
+    #[account(
+        mut,
+        constraint = token_mint.key() == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+// Remaining accounts, one [member_account, member_wallet] pair per member in `member_addresses` order
+pub fn close_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClosePool<'info>>,
+    _uuid: [u8; 6],
+) -> Result<()> {
     let group_account = &ctx.accounts.group_account;
-    let vault_sol = &ctx.accounts.vault_sol;
-    let signer = &ctx.accounts.signer;
-
-    // Check if pool is completed
-    require!(
-        matches!(group_account.status, PoolStatus::Completed),
-        HuiFiError::InvalidPoolStatus
-    );
+    let group_key = group_account.key();
+    let is_native_sol = group_account.config.is_native_sol;
 
     // Check if there are any unclaimed payouts
     require!(
         group_account.unclaimed_payout == 0,
         HuiFiError::UnclaimedPayouts
     );
+    require!(
+        ctx.remaining_accounts.len() == group_account.member_addresses.len() * 2,
+        HuiFiError::InvalidRemainingAccounts
+    );
 
-    // If there's any SOL left in the vault, transfer it back to creator
-    let vault_balance = vault_sol.lamports();
-    if vault_balance > 0 {
-        **vault_sol.try_borrow_mut_lamports()? = 0;
-        **signer.try_borrow_mut_lamports()? = signer
-            .lamports()
-            .checked_add(vault_balance)
-            .ok_or(HuiFiError::Overflow)?;
+    // Close every member account back to its owner
+    for (member_key, accounts) in group_account
+        .member_addresses
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(2))
+    {
+        let member_account = Account::<MemberAccount>::try_from(&accounts[0])?;
+        let member_wallet = &accounts[1];
+        require_keys_eq!(member_wallet.key(), *member_key, HuiFiError::NotPoolMember);
+        require!(
+            member_account.pool == group_key && member_account.owner == *member_key,
+            HuiFiError::NotPoolMember
+        );
+        require!(member_account.collateral_staked == 0, HuiFiError::CollateralNotWithdrawn);
+        // Every member picked so far, this cycle included, must have claimed
+        let is_owed_payout = group_account.is_past_recipient(member_key)
+            || group_account.current_winner == Some(*member_key);
+        require!(
+            !is_owed_payout || member_account.has_received_payout,
+            HuiFiError::UnclaimedPayouts
+        );
+
+        member_account.close(member_wallet.clone())?;
     }
 
+    // Sweep vault dust to the treasury and vault rent to the creator
+    let treasury = ctx.accounts.protocol_settings
+        .treasury_for_pool(group_account.token_mint, is_native_sol)
+        .ok_or(HuiFiError::UnsupportedToken)?
+        .treasury;
+    let mut dust: u64 = 0;
+    if is_native_sol {
+        let treasury_sol = ctx.accounts.treasury_sol.as_ref().ok_or(HuiFiError::InvalidTreasuryAccount)?;
+        require_keys_eq!(treasury_sol.key(), treasury, HuiFiError::InvalidTreasuryAccount);
+        let vault_rent = Rent::get()?.minimum_balance(0);
+
+        let vaults = [
+            (ctx.accounts.vault_sol.as_ref().ok_or(HuiFiError::MissingPoolVault)?, VAULT_SOL_SEED, ctx.bumps.vault_sol),
+            (ctx.accounts.collateral_vault_sol.as_ref().ok_or(HuiFiError::MissingCollateralVault)?, COLLATERAL_VAULT_SOL_SEED, ctx.bumps.collateral_vault_sol),
+        ];
+        for (vault, seed, bump) in vaults {
+            let vault_seeds: &[&[u8]] = &[seed, group_key.as_ref(), &[bump.unwrap()]];
+            let balance = vault.lamports();
+            let vault_dust = balance.saturating_sub(vault_rent);
+            if vault_dust > 0 {
+                transfer_from_sol_vault(vault, treasury_sol, &ctx.accounts.system_program, vault_seeds, vault_dust)?;
+            }
+            if balance > vault_dust {
+                transfer_from_sol_vault(vault, &ctx.accounts.signer, &ctx.accounts.system_program, vault_seeds, balance - vault_dust)?;
+            }
+            dust = dust.checked_add(vault_dust).ok_or(HuiFiError::Overflow)?;
+        }
+    } else {
+        let protocol_treasury = ctx.accounts.protocol_treasury.as_mut().ok_or(HuiFiError::InvalidTreasuryAccount)?;
+        require_keys_eq!(protocol_treasury.key(), treasury, HuiFiError::InvalidTreasuryAccount);
+        let token_mint = ctx.accounts.token_mint.as_ref().ok_or(HuiFiError::MissingTokenMint)?;
+        let token_program = ctx.accounts.token_program.as_ref().ok_or(HuiFiError::MissingTokenProgram)?;
+        let group_seeds = &[POOL_SEED, group_account.uuid.as_ref(), &[group_account.bump]];
+
+        let vaults = [
+            ctx.accounts.vault_spl.as_ref().ok_or(HuiFiError::MissingPoolVault)?,
+            ctx.accounts.collateral_vault_spl.as_ref().ok_or(HuiFiError::MissingCollateralVault)?,
+        ];
+        for vault in vaults {
+            if vault.amount > 0 {
+                let received = transfer_tokens(
+                    token_program,
+                    vault,
+                    protocol_treasury,
+                    token_mint,
+                    group_account.to_account_info(),
+                    &[group_seeds],
+                    vault.amount,
+                )?;
+                dust = dust.checked_add(received).ok_or(HuiFiError::Overflow)?;
+            }
+            close_token_account(
+                token_program,
+                vault,
+                token_mint,
+                ctx.accounts.signer.to_account_info(),
+                group_account.to_account_info(),
+                &[group_seeds],
+            )?;
+        }
+    }
+    if dust > 0 {
+        let token_mint = group_account.token_mint;
+        ctx.accounts.protocol_settings.record_fee(token_mint, is_native_sol, dust)?;
+    }
+    ctx.accounts.protocol_settings.release_pool(group_account.token_mint, is_native_sol);
+
+    emit!(PoolClosed {
+        pool: group_key,
+        creator: ctx.accounts.signer.key(),
+        members_closed: group_account.member_addresses.len() as u8,
+        dust,
+    });
+
     msg!("🗑️ Pool closed and accounts cleaned up");
-    msg!("💰 Remaining balance returned to creator: {} lamports", vault_balance);
+    msg!("💰 Dust swept to treasury: {}", dust);
     Ok(())
 }
 
+fn transfer_from_sol_vault<'info>(
+    vault: &UncheckedAccount<'info>,
+    to: &impl ToAccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    vault_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: vault.to_account_info(),
+                to: to.to_account_info(),
+            },
+            &[vault_seeds],
+        ),
+        amount,
+    )
+}
+
+#[event]
+pub struct PoolClosed {
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+    pub members_closed: u8,
+    pub dust: u64,
+}
+
+#[event]
+pub struct BiddingFinalized {
+    pub pool: Pubkey,
+    pub cycle: u8,
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub tied_bids: u8, // Revealed bids sharing the winning amount, 1 when there was no tie
+    pub tie_break_rule: TieBreakRule,
+}
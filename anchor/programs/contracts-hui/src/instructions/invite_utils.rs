@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+
+use crate::constants::*;
+use crate::errors::*;
+use crate::state::{GroupAccount, InviteTicket};
+
+// ==================== INVITE HELPERS ====================
+
+// num_signatures, padding, then one set of seven u16 offsets
+const ED25519_HEADER_LEN: usize = 16;
+
+// Gate joins on a whitelist entry or a signed invite ticket.
+// Public pools with an empty whitelist stay open to everyone.
+pub fn check_join_access(
+    group_account: &mut GroupAccount,
+    user: &Pubkey,
+    ticket: Option<InviteTicket>,
+    instructions_sysvar: Option<&UncheckedAccount>,
+) -> Result<()> {
+    match ticket {
+        Some(ticket) => {
+            let instructions_sysvar = instructions_sysvar.ok_or(HuiFiError::InvalidInviteTicket)?;
+            verify_ticket_signature(instructions_sysvar, &group_account.creator, &ticket)?;
+            redeem_ticket(group_account, user, &ticket)
+        }
+        None => {
+            if group_account.config.is_private || !group_account.whitelist.is_empty() {
+                require!(
+                    group_account.whitelist.contains(user),
+                    HuiFiError::NotWhitelisted
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+// Bytes the creator signs off-chain
+pub fn invite_ticket_message(ticket: &InviteTicket) -> Result<Vec<u8>> {
+    let mut message = INVITE_TICKET_DOMAIN.to_vec();
+    ticket.serialize(&mut message)?;
+    Ok(message)
+}
+
+// The ed25519 program instruction right before this one must verify the creator's
+// signature over the ticket, with every offset pointing into its own data
+fn verify_ticket_signature(
+    instructions_sysvar: &AccountInfo,
+    creator: &Pubkey,
+    ticket: &InviteTicket,
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, HuiFiError::InvalidInviteTicket);
+    let ix = load_instruction_at_checked((current_index - 1) as usize, instructions_sysvar)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, HuiFiError::InvalidInviteTicket);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LEN && data[0] == 1,
+        HuiFiError::InvalidInviteTicket
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let signature_ix = read_u16(4);
+    let public_key_offset = read_u16(6);
+    let public_key_ix = read_u16(8);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12);
+    let message_ix = read_u16(14);
+    let this_ix = u16::MAX as usize;
+    require!(
+        signature_ix == this_ix && public_key_ix == this_ix && message_ix == this_ix,
+        HuiFiError::InvalidInviteTicket
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(HuiFiError::InvalidInviteTicket)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(HuiFiError::InvalidInviteTicket)?;
+    require!(public_key == creator.as_ref(), HuiFiError::InvalidInviteTicket);
+    require!(
+        message == invite_ticket_message(ticket)?.as_slice(),
+        HuiFiError::InvalidInviteTicket
+    );
+    Ok(())
+}
+
+fn redeem_ticket(group_account: &mut GroupAccount, user: &Pubkey, ticket: &InviteTicket) -> Result<()> {
+    require!(ticket.pool_uuid == group_account.uuid, HuiFiError::InvalidInviteTicket);
+    if let Some(invitee) = ticket.invitee {
+        require_keys_eq!(invitee, *user, HuiFiError::InvalidInviteTicket);
+    }
+    require!(
+        Clock::get()?.unix_timestamp <= ticket.expires_at,
+        HuiFiError::InviteTicketExpired
+    );
+
+    // Each nonce opens exactly one seat
+    let nonce = ticket.nonce as usize;
+    require!(nonce < MAX_INVITE_NONCES, HuiFiError::InvalidInviteTicket);
+    let (byte, bit) = (nonce / 8, 1u8 << (nonce % 8));
+    require!(
+        group_account.used_invite_nonces[byte] & bit == 0,
+        HuiFiError::InviteTicketUsed
+    );
+    group_account.used_invite_nonces[byte] |= bit;
+
+    msg!("🎟️ Invite ticket {} redeemed by {}", ticket.nonce, user);
+    Ok(())
+}
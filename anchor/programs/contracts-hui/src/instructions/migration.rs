@@ -0,0 +1,715 @@
+use std::ops::RangeInclusive;
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::state::*;
+use crate::constants::*;
+use crate::errors::*;
+
+// ==================== ACCOUNT MIGRATION ====================
+//
+// Accounts created before versioning were sized with `std::mem::size_of` and
+// decode with `version == 0`. Migration is permissionless: it decodes the
+// account with the layout its version was written in, upgrades it and writes
+// it back at its current size, with the payer covering any extra rent.
+//
+// The version byte sits near the end of every account, so layouts are tried
+// newest first. A layout is only kept if its version byte is one it was
+// written at and its bump re-derives the account's own address.
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Owner is checked here, the discriminator when the account is decoded
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMemberAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Owner is checked here, the discriminator when the account is decoded
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    /// CHECK: The member's pool, decoded at whatever version it is on
+    #[account(owner = crate::ID)]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+//MIGRATE PROTOCOL SETTINGS
+pub fn migrate_protocol_settings(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account_info = ctx.accounts.account.to_account_info();
+    let settings = decode_protocol_settings(&account_info)?;
+
+    migrate_account(
+        &ctx.accounts.payer,
+        &account_info,
+        &ctx.accounts.system_program,
+        settings,
+        PROTOCOL_SETTINGS_VERSION,
+        |settings| &mut settings.version,
+        |_| 8 + ProtocolSettings::INIT_SPACE,
+    )
+}
+
+//MIGRATE GROUP ACCOUNT
+pub fn migrate_group_account(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account_info = ctx.accounts.account.to_account_info();
+    let group = decode_group_account(&account_info)?;
+
+    migrate_account(
+        &ctx.accounts.payer,
+        &account_info,
+        &ctx.accounts.system_program,
+        group,
+        GROUP_ACCOUNT_VERSION,
+        |group| &mut group.version,
+        |group| GroupAccount::space(group.whitelist.len()),
+    )
+}
+
+//MIGRATE MEMBER ACCOUNT
+pub fn migrate_member_account(ctx: Context<MigrateMemberAccount>) -> Result<()> {
+    let account_info = ctx.accounts.account.to_account_info();
+    let pool = decode_group_account(&ctx.accounts.pool.to_account_info())?;
+    let member = decode_member_account(&account_info, pool.current_cycle)?;
+    require_keys_eq!(member.pool, ctx.accounts.pool.key(), HuiFiError::NotPoolMember);
+
+    migrate_account(
+        &ctx.accounts.payer,
+        &account_info,
+        &ctx.accounts.system_program,
+        member,
+        MEMBER_ACCOUNT_VERSION,
+        |member| &mut member.version,
+        |_| 8 + MemberAccount::INIT_SPACE,
+    )
+}
+
+//MIGRATE BID STATE
+pub fn migrate_bid_state(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account_info = ctx.accounts.account.to_account_info();
+    let bid_state = decode_bid_state(&account_info)?;
+
+    migrate_account(
+        &ctx.accounts.payer,
+        &account_info,
+        &ctx.accounts.system_program,
+        bid_state,
+        BID_STATE_VERSION,
+        |bid_state| &mut bid_state.version,
+        |bid_state| BidState::space(bid_state.bids.len()),
+    )
+}
+
+// Upgrade a decoded account and write it back at its current size
+fn migrate_account<'info, T: AccountSerialize>(
+    payer: &Signer<'info>,
+    account_info: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    mut state: T,
+    current_version: u8,
+    version: impl Fn(&mut T) -> &mut u8,
+    space: impl Fn(&T) -> usize,
+) -> Result<()> {
+    let old_version = *version(&mut state);
+    require!(old_version < current_version, HuiFiError::AlreadyMigrated);
+    *version(&mut state) = current_version;
+
+    // Never shrink, only grow to fit the current layout
+    let new_space = space(&state).max(account_info.data_len());
+    if new_space > account_info.data_len() {
+        let rent_due = Rent::get()?
+            .minimum_balance(new_space)
+            .saturating_sub(account_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        account_info.realloc(new_space, false)?;
+    }
+
+    let mut data = account_info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    state.try_serialize(&mut writer)?;
+
+    emit!(AccountMigrated {
+        account: account_info.key(),
+        old_version,
+        new_version: current_version,
+        space: new_space as u64,
+    });
+    msg!("🔧 Migrated {} from v{} to v{}", account_info.key(), old_version, current_version);
+    Ok(())
+}
+
+// Account data past the discriminator, zero-padded so trailing fields missing
+// from older layouts decode as zero
+fn padded_body<T: Discriminator>(account_info: &AccountInfo, max_space: usize) -> Result<Vec<u8>> {
+    let data = account_info.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == *T::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let mut body = data[8..].to_vec();
+    if body.len() < max_space - 8 {
+        body.resize(max_space - 8, 0);
+    }
+    Ok(body)
+}
+
+fn is_layout_match(key: &Pubkey, versions: RangeInclusive<u8>, version: u8, seeds: &[&[u8]], bump: u8) -> bool {
+    let bump = [bump];
+    let seeds = [seeds, &[bump.as_ref()]].concat();
+    versions.contains(&version)
+        && Pubkey::create_program_address(&seeds, &crate::ID).ok() == Some(*key)
+}
+
+// ==================== PROTOCOL SETTINGS LAYOUTS ====================
+
+// Every ProtocolSettings layout so far, only the treasury entries have changed shape.
+// Fields after `bump` are zero before v1.
+#[derive(AnchorDeserialize)]
+struct ProtocolSettingsLayout<T> {
+    admin: Pubkey,
+    treasury_accounts: Vec<T>,
+    penalty_bps: u16,
+    fee_bps: u16,
+    create_pool_fee: u64,
+    total_fees_collected: u64,
+    yield_generated: u64,
+    reserve_buffer: u64,
+    bump: u8,
+    pending_admin: Option<Pubkey>,
+    fee_exempt_creators: Vec<Pubkey>,
+    paused_flags: u16,
+    role_members: Vec<RoleMember>,
+    version: u8,
+}
+
+// v0, v1 added the open pool count and the per-mint create pool fee
+#[derive(AnchorDeserialize)]
+struct TreasuryAccountV0 {
+    token_mint: Option<Pubkey>,
+    treasury: Pubkey,
+    total_collected: u64,
+    is_native_sol: bool,
+}
+
+impl From<TreasuryAccountV0> for TreasuryAccount {
+    fn from(treasury: TreasuryAccountV0) -> Self {
+        Self {
+            token_mint: treasury.token_mint,
+            treasury: treasury.treasury,
+            total_collected: treasury.total_collected,
+            is_native_sol: treasury.is_native_sol,
+            open_pools: 0,
+            create_pool_fee: 0,
+        }
+    }
+}
+
+fn decode_protocol_settings(account_info: &AccountInfo) -> Result<ProtocolSettings> {
+    let body = padded_body::<ProtocolSettings>(account_info, 8 + ProtocolSettings::INIT_SPACE)?;
+    let key = account_info.key();
+
+    decode_settings_layout::<TreasuryAccount>(&body, &key, 1..=PROTOCOL_SETTINGS_VERSION)
+        .or_else(|| decode_settings_layout::<TreasuryAccountV0>(&body, &key, 0..=0))
+        .ok_or(ErrorCode::AccountDidNotDeserialize.into())
+}
+
+fn decode_settings_layout<T: AnchorDeserialize + Into<TreasuryAccount>>(
+    body: &[u8],
+    key: &Pubkey,
+    versions: RangeInclusive<u8>,
+) -> Option<ProtocolSettings> {
+    let settings = ProtocolSettingsLayout::<T>::deserialize(&mut &body[..]).ok()?;
+    if !is_layout_match(key, versions, settings.version, &[PROTOCOL_SEED], settings.bump) {
+        return None;
+    }
+
+    Some(ProtocolSettings {
+        admin: settings.admin,
+        treasury_accounts: settings.treasury_accounts.into_iter().map(Into::into).collect(),
+        penalty_bps: settings.penalty_bps,
+        fee_bps: settings.fee_bps,
+        create_pool_fee: settings.create_pool_fee,
+        total_fees_collected: settings.total_fees_collected,
+        yield_generated: settings.yield_generated,
+        reserve_buffer: settings.reserve_buffer,
+        bump: settings.bump,
+        pending_admin: settings.pending_admin,
+        fee_exempt_creators: settings.fee_exempt_creators,
+        paused_flags: settings.paused_flags,
+        role_members: settings.role_members,
+        version: settings.version,
+    })
+}
+
+// ==================== GROUP ACCOUNT LAYOUTS ====================
+
+// Every GroupAccount layout so far, only the config has changed shape in between
+#[derive(AnchorDeserialize)]
+struct GroupAccountLayout<C> {
+    uuid: [u8; 6],
+    whitelist: Vec<Pubkey>,
+    creator: Pubkey,
+    token_mint: Pubkey,
+    vault: Pubkey,
+    config: C,
+    member_addresses: Vec<Pubkey>,
+    payout_order: Vec<Pubkey>,
+    current_cycle: u8,
+    total_cycles: u8,
+    status: PoolStatus,
+    total_contributions: u64,
+    unclaimed_payout: u64,
+    last_cycle_timestamp: i64,
+    next_payout_timestamp: i64,
+    price_feed_id: [u8; 32],
+    current_winner: Option<Pubkey>,
+    current_bid_amount: Option<u64>,
+    final_contribution_amount: Option<u64>,
+    bump: u8,
+    protocol_fee_bps: u16,           // Zero before v1
+    penalty_bps: u16,                // Zero before v1
+    version: u8,
+    used_invite_nonces: [u8; MAX_INVITE_NONCES / 8], // Zero before v4
+    cycle_contributors: u8,          // Zero before v6, counts from the next cycle on older pools
+}
+
+// v0 and v1
+#[derive(AnchorDeserialize)]
+struct PoolConfigV1 {
+    max_participants: u8,
+    contribution_amount: u64,
+    cycle_duration_seconds: u64,
+    payout_delay_seconds: u64,
+    early_withdrawal_fee_bps: u16,
+    collateral_requirement_bps: u16,
+    yield_strategy: YieldPlatform,
+    is_private: bool,
+    is_native_sol: bool,
+    feed_id: [u8; 32],
+}
+
+// v2 added the join deadline
+impl From<PoolConfigV1> for PoolConfig {
+    fn from(v1: PoolConfigV1) -> Self {
+        PoolConfigV2 {
+            max_participants: v1.max_participants,
+            contribution_amount: v1.contribution_amount,
+            cycle_duration_seconds: v1.cycle_duration_seconds,
+            payout_delay_seconds: v1.payout_delay_seconds,
+            early_withdrawal_fee_bps: v1.early_withdrawal_fee_bps,
+            collateral_requirement_bps: v1.collateral_requirement_bps,
+            yield_strategy: v1.yield_strategy,
+            is_private: v1.is_private,
+            is_native_sol: v1.is_native_sol,
+            feed_id: v1.feed_id,
+            // Older pools never expire
+            join_deadline: i64::MAX,
+        }
+        .into()
+    }
+}
+
+// v2
+#[derive(AnchorDeserialize)]
+struct PoolConfigV2 {
+    max_participants: u8,
+    contribution_amount: u64,
+    cycle_duration_seconds: u64,
+    payout_delay_seconds: u64,
+    early_withdrawal_fee_bps: u16,
+    collateral_requirement_bps: u16,
+    yield_strategy: YieldPlatform,
+    is_private: bool,
+    is_native_sol: bool,
+    feed_id: [u8; 32],
+    join_deadline: i64,
+}
+
+// v3 added the quorum for an early start
+impl From<PoolConfigV2> for PoolConfig {
+    fn from(v2: PoolConfigV2) -> Self {
+        PoolConfigV3 {
+            max_participants: v2.max_participants,
+            contribution_amount: v2.contribution_amount,
+            cycle_duration_seconds: v2.cycle_duration_seconds,
+            payout_delay_seconds: v2.payout_delay_seconds,
+            early_withdrawal_fee_bps: v2.early_withdrawal_fee_bps,
+            collateral_requirement_bps: v2.collateral_requirement_bps,
+            yield_strategy: v2.yield_strategy,
+            is_private: v2.is_private,
+            is_native_sol: v2.is_native_sol,
+            feed_id: v2.feed_id,
+            join_deadline: v2.join_deadline,
+            // Older pools only start once full
+            min_participants: v2.max_participants,
+        }
+        .into()
+    }
+}
+
+// v3 and v4
+#[derive(AnchorDeserialize)]
+struct PoolConfigV3 {
+    max_participants: u8,
+    contribution_amount: u64,
+    cycle_duration_seconds: u64,
+    payout_delay_seconds: u64,
+    early_withdrawal_fee_bps: u16,
+    collateral_requirement_bps: u16,
+    yield_strategy: YieldPlatform,
+    is_private: bool,
+    is_native_sol: bool,
+    feed_id: [u8; 32],
+    join_deadline: i64,
+    min_participants: u8,
+}
+
+// v5 added the payout mode
+impl From<PoolConfigV3> for PoolConfig {
+    fn from(v3: PoolConfigV3) -> Self {
+        PoolConfigV4 {
+            max_participants: v3.max_participants,
+            contribution_amount: v3.contribution_amount,
+            cycle_duration_seconds: v3.cycle_duration_seconds,
+            payout_delay_seconds: v3.payout_delay_seconds,
+            early_withdrawal_fee_bps: v3.early_withdrawal_fee_bps,
+            collateral_requirement_bps: v3.collateral_requirement_bps,
+            yield_strategy: v3.yield_strategy,
+            is_private: v3.is_private,
+            is_native_sol: v3.is_native_sol,
+            feed_id: v3.feed_id,
+            join_deadline: v3.join_deadline,
+            min_participants: v3.min_participants,
+            // Older pools always auctioned the pot
+            payout_mode: PayoutMode::Auction,
+        }
+        .into()
+    }
+}
+
+// v5 and v6
+#[derive(AnchorDeserialize)]
+struct PoolConfigV4 {
+    max_participants: u8,
+    contribution_amount: u64,
+    cycle_duration_seconds: u64,
+    payout_delay_seconds: u64,
+    early_withdrawal_fee_bps: u16,
+    collateral_requirement_bps: u16,
+    yield_strategy: YieldPlatform,
+    is_private: bool,
+    is_native_sol: bool,
+    feed_id: [u8; 32],
+    join_deadline: i64,
+    min_participants: u8,
+    payout_mode: PayoutMode,
+}
+
+// v7 added the bid bounds
+impl From<PoolConfigV4> for PoolConfig {
+    fn from(v4: PoolConfigV4) -> Self {
+        // Older pools take the default bid bounds
+        let defaults = PoolConfig::default();
+        PoolConfigV5 {
+            max_participants: v4.max_participants,
+            contribution_amount: v4.contribution_amount,
+            cycle_duration_seconds: v4.cycle_duration_seconds,
+            payout_delay_seconds: v4.payout_delay_seconds,
+            early_withdrawal_fee_bps: v4.early_withdrawal_fee_bps,
+            collateral_requirement_bps: v4.collateral_requirement_bps,
+            yield_strategy: v4.yield_strategy,
+            is_private: v4.is_private,
+            is_native_sol: v4.is_native_sol,
+            feed_id: v4.feed_id,
+            join_deadline: v4.join_deadline,
+            min_participants: v4.min_participants,
+            payout_mode: v4.payout_mode,
+            min_discount_bps: defaults.min_discount_bps,
+            max_discount_bps: defaults.max_discount_bps,
+        }
+        .into()
+    }
+}
+
+// v7
+#[derive(AnchorDeserialize)]
+struct PoolConfigV5 {
+    max_participants: u8,
+    contribution_amount: u64,
+    cycle_duration_seconds: u64,
+    payout_delay_seconds: u64,
+    early_withdrawal_fee_bps: u16,
+    collateral_requirement_bps: u16,
+    yield_strategy: YieldPlatform,
+    is_private: bool,
+    is_native_sol: bool,
+    feed_id: [u8; 32],
+    join_deadline: i64,
+    min_participants: u8,
+    payout_mode: PayoutMode,
+    min_discount_bps: u16,
+    max_discount_bps: u16,
+}
+
+// v8 added the tie-break rule
+impl From<PoolConfigV5> for PoolConfig {
+    fn from(v5: PoolConfigV5) -> Self {
+        Self {
+            max_participants: v5.max_participants,
+            contribution_amount: v5.contribution_amount,
+            cycle_duration_seconds: v5.cycle_duration_seconds,
+            payout_delay_seconds: v5.payout_delay_seconds,
+            early_withdrawal_fee_bps: v5.early_withdrawal_fee_bps,
+            collateral_requirement_bps: v5.collateral_requirement_bps,
+            yield_strategy: v5.yield_strategy,
+            is_private: v5.is_private,
+            is_native_sol: v5.is_native_sol,
+            feed_id: v5.feed_id,
+            join_deadline: v5.join_deadline,
+            min_participants: v5.min_participants,
+            payout_mode: v5.payout_mode,
+            min_discount_bps: v5.min_discount_bps,
+            max_discount_bps: v5.max_discount_bps,
+            // Older pools settled ties by commit order
+            tie_break_rule: TieBreakRule::EarliestBid,
+        }
+    }
+}
+
+fn decode_group_account(account_info: &AccountInfo) -> Result<GroupAccount> {
+    let body = padded_body::<GroupAccount>(account_info, 8 + GroupAccount::INIT_SPACE)?;
+    let key = account_info.key();
+
+    decode_group_layout::<PoolConfig>(&body, &key, 8..=GROUP_ACCOUNT_VERSION)
+        .or_else(|| decode_group_layout::<PoolConfigV5>(&body, &key, 7..=7))
+        .or_else(|| decode_group_layout::<PoolConfigV4>(&body, &key, 5..=6))
+        .or_else(|| decode_group_layout::<PoolConfigV3>(&body, &key, 3..=4))
+        .or_else(|| decode_group_layout::<PoolConfigV2>(&body, &key, 2..=2))
+        .or_else(|| decode_group_layout::<PoolConfigV1>(&body, &key, 0..=1))
+        .ok_or(ErrorCode::AccountDidNotDeserialize.into())
+}
+
+fn decode_group_layout<C: AnchorDeserialize + Into<PoolConfig>>(
+    body: &[u8],
+    key: &Pubkey,
+    versions: RangeInclusive<u8>,
+) -> Option<GroupAccount> {
+    let group = GroupAccountLayout::<C>::deserialize(&mut &body[..]).ok()?;
+    if !is_layout_match(key, versions, group.version, &[POOL_SEED, &group.uuid], group.bump) {
+        return None;
+    }
+
+    Some(GroupAccount {
+        uuid: group.uuid,
+        whitelist: group.whitelist,
+        creator: group.creator,
+        token_mint: group.token_mint,
+        vault: group.vault,
+        config: group.config.into(),
+        member_addresses: group.member_addresses,
+        payout_order: group.payout_order,
+        current_cycle: group.current_cycle,
+        total_cycles: group.total_cycles,
+        status: group.status,
+        total_contributions: group.total_contributions,
+        unclaimed_payout: group.unclaimed_payout,
+        last_cycle_timestamp: group.last_cycle_timestamp,
+        next_payout_timestamp: group.next_payout_timestamp,
+        price_feed_id: group.price_feed_id,
+        current_winner: group.current_winner,
+        current_bid_amount: group.current_bid_amount,
+        final_contribution_amount: group.final_contribution_amount,
+        bump: group.bump,
+        protocol_fee_bps: group.protocol_fee_bps,
+        penalty_bps: group.penalty_bps,
+        version: group.version,
+        used_invite_nonces: group.used_invite_nonces,
+        cycle_contributors: group.cycle_contributors,
+    })
+}
+
+// ==================== MEMBER ACCOUNT LAYOUTS ====================
+
+// v0 and v1, per-cycle stamps were plain flags. v0 is v1 without the trailing version
+#[derive(AnchorDeserialize)]
+struct MemberAccountV1 {
+    owner: Pubkey,
+    pool: Pubkey,
+    contributions_made: u8,
+    has_bid: bool,
+    has_contributed: bool,
+    status: MemberStatus,
+    has_received_payout: bool,
+    eligible_for_payout: bool,
+    collateral_staked: u64,
+    reputation_points: u64,
+    last_contribution_timestamp: i64,
+    total_contributions: u64,
+    has_deposited_collateral: bool,
+    payout_amount: u64,
+    bump: u8,
+    version: u8,
+}
+
+impl MemberAccountV1 {
+    // The old flags were never reset, so a set flag is taken to cover the pool's current cycle
+    fn upgrade(self, current_cycle: u8) -> MemberAccount {
+        MemberAccount {
+            owner: self.owner,
+            pool: self.pool,
+            contributions_made: self.contributions_made,
+            last_bid_cycle: self.has_bid.then_some(current_cycle),
+            last_contribution_cycle: self.has_contributed.then_some(current_cycle),
+            status: self.status,
+            has_received_payout: self.has_received_payout,
+            eligible_for_payout: self.eligible_for_payout,
+            collateral_staked: self.collateral_staked,
+            reputation_points: self.reputation_points,
+            last_contribution_timestamp: self.last_contribution_timestamp,
+            total_contributions: self.total_contributions,
+            has_deposited_collateral: self.has_deposited_collateral,
+            payout_amount: self.payout_amount,
+            bump: self.bump,
+            version: self.version,
+        }
+    }
+}
+
+fn decode_member_account(account_info: &AccountInfo, current_cycle: u8) -> Result<MemberAccount> {
+    let body = padded_body::<MemberAccount>(account_info, 8 + MemberAccount::INIT_SPACE)?;
+    let key = account_info.key();
+    let is_match = |versions, version, owner: &Pubkey, pool: &Pubkey, bump| {
+        is_layout_match(&key, versions, version, &[MEMBER_SEED, pool.as_ref(), owner.as_ref()], bump)
+    };
+
+    if let Ok(member) = MemberAccount::deserialize(&mut body.as_slice()) {
+        if is_match(2..=MEMBER_ACCOUNT_VERSION, member.version, &member.owner, &member.pool, member.bump) {
+            return Ok(member);
+        }
+    }
+    if let Ok(member) = MemberAccountV1::deserialize(&mut body.as_slice()) {
+        if is_match(0..=1, member.version, &member.owner, &member.pool, member.bump) {
+            return Ok(member.upgrade(current_cycle));
+        }
+    }
+    err!(ErrorCode::AccountDidNotDeserialize)
+}
+
+// ==================== BID STATE LAYOUTS ====================
+
+// Every BidState layout so far, only the bid entries have changed shape
+#[derive(AnchorDeserialize)]
+struct BidStateLayout<E> {
+    pool: Pubkey,
+    cycle: u8,
+    bids: Vec<E>,
+    winner: Option<Pubkey>,
+    bump: u8,
+    version: u8,
+}
+
+// v0 and v1, bids were plaintext
+#[derive(AnchorDeserialize)]
+struct BidEntryV1 {
+    bidder: Pubkey,
+    amount: u64,
+}
+
+// v2 sealed bids
+impl From<BidEntryV1> for BidEntry {
+    fn from(bid: BidEntryV1) -> Self {
+        // Plaintext bids were already public, so they count as revealed
+        BidEntryV2 {
+            bidder: bid.bidder,
+            amount: bid.amount,
+            commitment: [0; 32],
+            revealed: true,
+        }
+        .into()
+    }
+}
+
+// v2
+#[derive(AnchorDeserialize)]
+struct BidEntryV2 {
+    bidder: Pubkey,
+    amount: u64,
+    commitment: [u8; 32],
+    revealed: bool,
+}
+
+// v3 added the commit time
+impl From<BidEntryV2> for BidEntry {
+    fn from(bid: BidEntryV2) -> Self {
+        Self {
+            bidder: bid.bidder,
+            amount: bid.amount,
+            commitment: bid.commitment,
+            revealed: bid.revealed,
+            // Older bids all tie on commit time, so commit order settles an EarliestBid tie
+            committed_at: 0,
+        }
+    }
+}
+
+fn decode_bid_state(account_info: &AccountInfo) -> Result<BidState> {
+    let body = padded_body::<BidState>(account_info, 8 + BidState::INIT_SPACE)?;
+    let key = account_info.key();
+
+    decode_bid_layout::<BidEntry>(&body, &key, 3..=BID_STATE_VERSION)
+        .or_else(|| decode_bid_layout::<BidEntryV2>(&body, &key, 2..=2))
+        .or_else(|| decode_bid_layout::<BidEntryV1>(&body, &key, 0..=1))
+        .ok_or(ErrorCode::AccountDidNotDeserialize.into())
+}
+
+fn decode_bid_layout<E: AnchorDeserialize + Into<BidEntry>>(
+    body: &[u8],
+    key: &Pubkey,
+    versions: RangeInclusive<u8>,
+) -> Option<BidState> {
+    let bid_state = BidStateLayout::<E>::deserialize(&mut &body[..]).ok()?;
+    if !is_layout_match(key, versions, bid_state.version, &[BID_STATE_SEED, bid_state.pool.as_ref()], bid_state.bump) {
+        return None;
+    }
+
+    Some(BidState {
+        pool: bid_state.pool,
+        cycle: bid_state.cycle,
+        bids: bid_state.bids.into_iter().map(Into::into).collect(),
+        winner: bid_state.winner,
+        bump: bid_state.bump,
+        version: bid_state.version,
+    })
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub old_version: u8,
+    pub new_version: u8,
+    pub space: u64,
+}
@@ -5,10 +5,14 @@ pub mod collateral;
 pub mod contribution;
 pub mod bidding;
 pub mod cycle_management;
+pub mod migration;
+pub mod token_utils;
+pub mod invite_utils;
 pub use protocol::*;
 pub use pool::*;
 pub use payout::*;
 pub use collateral::*;
 pub use contribution::*;
 pub use bidding::*;
-pub use cycle_management::*;
\ No newline at end of file
+pub use cycle_management::*;
+pub use migration::*;
\ No newline at end of file
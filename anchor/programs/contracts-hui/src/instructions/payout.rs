@@ -1,10 +1,11 @@
 use crate::constants::*;
 use crate::errors::*;
 use crate::state::*;
+use crate::instructions::token_utils::{transfer_tokens, unwrap_sol};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
 use anchor_lang::system_program::{self};
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 #[instruction(uuid: [u8; 6])]
@@ -20,6 +21,7 @@ pub struct ProcessPayout<'info> {
             group_account.status,
             PoolStatus::Active { phase: CyclePhase::ReadyForPayout }
         ) @ HuiFiError::InvalidPhase,
+        constraint = group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
     )]
     pub group_account: Account<'info, GroupAccount>,
 
@@ -28,7 +30,6 @@ pub struct ProcessPayout<'info> {
         seeds = [MEMBER_SEED, group_account.key().as_ref(), user.key().as_ref()],
         bump = recipient_account.bump,
         constraint = Some(user.key()) == group_account.current_winner @ HuiFiError::NotPoolWinner,
-        constraint = recipient_account.eligible_for_payout @ HuiFiError::NotEligibleForPayout,
         constraint = !recipient_account.has_received_payout @ HuiFiError::AlreadyReceivedPayout,
     )]
     pub recipient_account: Account<'info, MemberAccount>,
@@ -46,8 +47,10 @@ pub struct ProcessPayout<'info> {
     pub vault_sol: AccountInfo<'info>,
 
     #[account(
+        mut,
         seeds = [PROTOCOL_SEED],
         bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_PAYOUT) @ HuiFiError::ProtocolPaused,
     )]
     pub protocol_settings: Account<'info, ProtocolSettings>,
 
@@ -67,13 +70,9 @@ pub fn process_payout(ctx: Context<ProcessPayout>, _uuid: [u8; 6]) -> Result<()>
     let group_account = &mut ctx.accounts.group_account;
     let recipient_account = &mut ctx.accounts.recipient_account;
     let current_timestamp = Clock::get()?.unix_timestamp;
-    let bps = 500;
+    let bps = group_account.protocol_fee_bps as u64;
     let group_key = group_account.to_account_info().key();
-    // Basic validations
-    require!(
-        recipient_account.eligible_for_payout,
-        HuiFiError::NotEligibleForPayout
-    );
+    // Basic validations, the cycle's winner is whoever current_winner names
     require!(
         !recipient_account.has_received_payout,
         HuiFiError::AlreadyReceivedPayout
@@ -83,6 +82,15 @@ pub fn process_payout(ctx: Context<ProcessPayout>, _uuid: [u8; 6]) -> Result<()>
         HuiFiError::PayoutDelayNotElapsed
     );
 
+    // Fees go to the treasury registered for the pool's mint
+    let treasury = ctx.accounts.protocol_settings
+        .treasury_for_pool(group_account.token_mint, group_account.config.is_native_sol)
+        .ok_or(HuiFiError::UnsupportedToken)?;
+    require!(
+        treasury.treasury == ctx.accounts.protocol_treasury.key(),
+        HuiFiError::InvalidTreasuryAccount
+    );
+
     // Check if this is the final cycle
     let is_final_cycle = group_account.current_cycle == group_account.total_cycles - 1;
 
@@ -106,8 +114,8 @@ pub fn process_payout(ctx: Context<ProcessPayout>, _uuid: [u8; 6]) -> Result<()>
         msg!("ℹ️ Final cycle - no collateral required");
     }
 
-    // Calculate payout amounts
-    let total_payout = recipient_account.payout_amount;
+    // The winner takes everything contributed this cycle
+    let total_payout = group_account.total_contributions;
 
     // Calculate fee for early payout
     let fee_amount = total_payout
@@ -150,13 +158,20 @@ pub fn process_payout(ctx: Context<ProcessPayout>, _uuid: [u8; 6]) -> Result<()>
             ),
             fee_amount,
         )?;
+        ctx.accounts.protocol_settings.record_fee(
+            group_account.token_mint,
+            group_account.config.is_native_sol,
+            fee_amount,
+        )?;
     }
 
     // Update accounts
     recipient_account.has_received_payout = true;
     recipient_account.eligible_for_payout = false;
+    recipient_account.payout_amount = total_payout;
     // recipient_account.status = MemberStatus::Withdrawed;
     group_account.total_contributions = 0; // Reset total contributions
+    group_account.unclaimed_payout = 0;
 
     // Log the transaction details
     msg!(
@@ -184,6 +199,173 @@ pub fn process_payout(ctx: Context<ProcessPayout>, _uuid: [u8; 6]) -> Result<()>
     Ok(())
 }
 
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct ProcessSplPayout<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = matches!(
+            group_account.status,
+            PoolStatus::Active { phase: CyclePhase::ReadyForPayout }
+        ) @ HuiFiError::InvalidPhase,
+        constraint = !group_account.config.is_native_sol @ HuiFiError::InvalidPoolType,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+
+    #[account(
+        mut,
+        seeds = [MEMBER_SEED, group_account.key().as_ref(), user.key().as_ref()],
+        bump = recipient_account.bump,
+        constraint = Some(user.key()) == group_account.current_winner @ HuiFiError::NotPoolWinner,
+        constraint = !recipient_account.has_received_payout @ HuiFiError::AlreadyReceivedPayout,
+    )]
+    pub recipient_account: Account<'info, MemberAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ HuiFiError::InvalidTokenAccountOwner,
+        constraint = user_token_account.mint == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub vault_spl: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_PAYOUT) @ HuiFiError::ProtocolPaused,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    // Treasury for the pool's mint, checked against the registry
+    #[account(mut)]
+    pub protocol_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// `unwrap_wsol` closes user_token_account into the wallet, only for a wSOL account made for this payout
+pub fn process_spl_payout(ctx: Context<ProcessSplPayout>, _uuid: [u8; 6], unwrap_wsol: bool) -> Result<()> {
+    let group_account = &mut ctx.accounts.group_account;
+    let recipient_account = &mut ctx.accounts.recipient_account;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let bps = group_account.protocol_fee_bps as u64;
+
+    require!(
+        current_timestamp >= group_account.next_payout_timestamp,
+        HuiFiError::PayoutDelayNotElapsed
+    );
+
+    // Fees go to the treasury registered for this mint
+    let treasury = ctx.accounts.protocol_settings
+        .treasury_for_pool(group_account.token_mint, group_account.config.is_native_sol)
+        .ok_or(HuiFiError::UnsupportedToken)?;
+    require!(
+        treasury.treasury == ctx.accounts.protocol_treasury.key(),
+        HuiFiError::InvalidTreasuryAccount
+    );
+
+    // Collateral is required for every cycle except the last
+    let is_final_cycle = group_account.current_cycle == group_account.total_cycles - 1;
+    if !is_final_cycle {
+        require!(
+            recipient_account.has_deposited_collateral,
+            HuiFiError::CollateralNotDeposited
+        );
+        msg!("✅ Collateral verification passed");
+    } else {
+        msg!("ℹ️ Final cycle - no collateral required");
+    }
+
+    // The winner takes everything contributed this cycle
+    let total_payout = group_account.total_contributions;
+    let fee_amount = total_payout
+        .saturating_mul(bps)
+        .saturating_div(BASIS_POINTS_DIVISOR);
+    let payout_amount = total_payout.saturating_sub(fee_amount);
+
+    require!(
+        ctx.accounts.vault_spl.amount >= total_payout,
+        HuiFiError::InsufficientVaultFunds
+    );
+
+    // The pool vault is owned by the pool PDA
+    let group_seeds = &[POOL_SEED, group_account.uuid.as_ref(), &[group_account.bump]];
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault_spl,
+        &mut ctx.accounts.user_token_account,
+        &ctx.accounts.token_mint,
+        group_account.to_account_info(),
+        &[group_seeds],
+        payout_amount,
+    )?;
+    // wSOL pools unwrap on exit when asked to
+    if unwrap_wsol {
+        require!(group_account.is_wrapped_sol(), HuiFiError::InvalidPoolType);
+        unwrap_sol(
+            &ctx.accounts.user_token_account,
+            ctx.accounts.user.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+    }
+
+    if fee_amount > 0 {
+        // Record what the treasury actually received
+        let fee_received = transfer_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_spl,
+            &mut ctx.accounts.protocol_treasury,
+            &ctx.accounts.token_mint,
+            group_account.to_account_info(),
+            &[group_seeds],
+            fee_amount,
+        )?;
+        ctx.accounts.protocol_settings.record_fee(
+            group_account.token_mint,
+            group_account.config.is_native_sol,
+            fee_received,
+        )?;
+    }
+
+    recipient_account.has_received_payout = true;
+    recipient_account.eligible_for_payout = false;
+    recipient_account.payout_amount = total_payout;
+    group_account.total_contributions = 0; // Reset total contributions
+    group_account.unclaimed_payout = 0;
+
+    msg!("✅ Processed payout of {} tokens to {}", payout_amount, ctx.accounts.user.key());
+    if fee_amount > 0 {
+        msg!("💰 Fee paid: {} tokens", fee_amount);
+    }
+
+    emit!(PayoutProcessed {
+        pool: group_account.key(),
+        recipient: ctx.accounts.user.key(),
+        amount: payout_amount,
+        cycle: group_account.current_cycle,
+        timestamp: current_timestamp,
+    });
+
+    Ok(())
+}
+
 #[event]
 pub struct PayoutProcessed {
     pub pool: Pubkey,
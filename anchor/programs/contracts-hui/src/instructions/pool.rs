@@ -408,16 +408,19 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{program::invoke, system_instruction};
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::state::*;
 use crate::constants::*;
 use crate::errors::*;
+use crate::instructions::token_utils::{validate_mint_extensions, transfer_tokens, wrap_sol, unwrap_sol, close_token_account};
+use crate::instructions::invite_utils::check_join_access;
 // ==================== CREATE POOL FUNCTIONS ====================
 
 //CREATE SOL POOL
 #[derive(Accounts)]
-#[instruction(pool_config: PoolConfig, uuid: [u8; 6])]
+#[instruction(pool_config: PoolConfig, uuid: [u8; 6], whitelist: Option<Vec<Pubkey>>)]
 pub struct CreateSolPool<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -425,7 +428,7 @@ pub struct CreateSolPool<'info> {
     #[account(
         init,
         payer = creator,
-        space = 8 + std::mem::size_of::<GroupAccount>(),
+        space = GroupAccount::space(whitelist.as_ref().map_or(0, |w| w.len())),
         seeds = [POOL_SEED, uuid.as_ref()],
         bump,
     )]
@@ -452,20 +455,8 @@ pub struct CreateSolPool<'info> {
     #[account(
         init,
         payer = creator,
-        // Calculate space for:
-        // - 8 bytes for discriminator
-        // - size of pool pubkey
-        // - size of cycle (u64)
-        // - size of winner (Option<Pubkey>)
-        // - size of bump (u8)
-        // - size of bids vector (vec length + max_bids * size_of_bid_entry)
-        space = 8 
-            + 32  // pool: Pubkey
-            + 8   // cycle: u64
-            + (32 + 1)  // winner: Option<Pubkey>
-            + 1   // bump: u8
-            + 4   // vec length prefix
-            + (32 + 8) * pool_config.max_participants as usize, // bidder: Pubkey + amount: u64 for each bid
+        // One bid slot per participant
+        space = BidState::space(pool_config.max_participants as usize),
         seeds = [BID_STATE_SEED, group_account.key().as_ref()],
         bump,
     )]
@@ -474,14 +465,38 @@ pub struct CreateSolPool<'info> {
     #[account(
         init,
         payer = creator,
-        space = 8 + std::mem::size_of::<MemberAccount>(),
+        space = 8 + MemberAccount::INIT_SPACE,
         seeds = [MEMBER_SEED, group_account.key().as_ref(), creator.key().as_ref()],
         bump,
     )]
     pub member_account: Account<'info, MemberAccount>,   
 
-    #[account(seeds = [PROTOCOL_SEED], bump = protocol_settings.bump)]
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_CREATE_POOL) @ HuiFiError::ProtocolPaused,
+    )]
     pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    /// CHECK: Protocol SOL treasury that receives the create pool fee
+    #[account(
+        mut,
+        owner = system_program.key(),
+        seeds = [TREASURY_SEED, b"sol"],
+        bump,
+    )]
+    pub treasury_sol: AccountInfo<'info>,
+
+    #[account(seeds = [LIMITS_SEED], bump = protocol_limits.bump)]
+    pub protocol_limits: Account<'info, ProtocolLimits>,
+
+    // SOL pools must be allowlisted under the native mint
+    #[account(
+        seeds = [MINT_CONFIG_SEED, anchor_spl::token::spl_token::native_mint::id().as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
     
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -494,6 +509,11 @@ pub fn create_sol_pool(
     whitelist: Option<Vec<Pubkey>>,
 ) -> Result<()> {
     let current_timestamp = Clock::get()?.unix_timestamp;
+    // Validate pool configuration
+    validate_pool_config(&pool_config, &ctx.accounts.protocol_limits.limits, &ctx.accounts.mint_config, current_timestamp)?;
+    let whitelist = whitelist.unwrap_or_default();
+    require!(whitelist.len() <= MAX_WHITELIST_SIZE, HuiFiError::WhitelistTooLarge);
+
     let group_account = &mut ctx.accounts.group_account;
     let bump = ctx.bumps.group_account;
     let member_account = &mut ctx.accounts.member_account;
@@ -506,7 +526,7 @@ pub fn create_sol_pool(
     // Initialize the group account
     group_account.uuid = uuid;
     group_account.creator = ctx.accounts.creator.key();
-    group_account.whitelist = whitelist.unwrap_or_default();
+    group_account.whitelist = whitelist;
     group_account.token_mint = anchor_spl::token::spl_token::native_mint::id();
     group_account.vault = ctx.accounts.vault_sol.key();
  
@@ -524,6 +544,12 @@ pub fn create_sol_pool(
     group_account.current_bid_amount = None;
     group_account.current_winner = None;
     group_account.bump = bump;
+    // Lock in the fees members agree to when joining
+    group_account.protocol_fee_bps = ctx.accounts.protocol_settings.fee_bps;
+    group_account.penalty_bps = ctx.accounts.protocol_settings.penalty_bps;
+    group_account.version = GROUP_ACCOUNT_VERSION;
+    group_account.used_invite_nonces = [0; MAX_INVITE_NONCES / 8];
+    group_account.cycle_contributors = 0;
 
     // Initialize current bid state
     let current_bid_state = &mut ctx.accounts.current_bid_state;
@@ -532,6 +558,7 @@ pub fn create_sol_pool(
     current_bid_state.bids = Vec::new();
     current_bid_state.winner = None;
     current_bid_state.bump = ctx.bumps.current_bid_state;
+    current_bid_state.version = BID_STATE_VERSION;
 
     // Initialize the creator's member account
     member_account.owner = ctx.accounts.creator.key();
@@ -545,11 +572,12 @@ pub fn create_sol_pool(
     member_account.reputation_points = 0;
     member_account.last_contribution_timestamp = 0;
     member_account.total_contributions = 0;
-    member_account.has_bid = false;
-    member_account.has_contributed = false;
+    member_account.last_bid_cycle = None;
+    member_account.last_contribution_cycle = None;
     member_account.has_deposited_collateral = false;
     member_account.payout_amount = 0;
-    member_account.bump = member_bump;   
+    member_account.bump = member_bump;
+    member_account.version = MEMBER_ACCOUNT_VERSION;
 
     // Create the vaults as PDAs
     let vault_lamports = Rent::get()?.minimum_balance(0);
@@ -582,10 +610,21 @@ pub fn create_sol_pool(
         ],
     )?;
 
+    // Charge the create pool fee
+    let pool_fee = charge_create_pool_fee(
+        &mut ctx.accounts.protocol_settings,
+        &ctx.accounts.creator,
+        &ctx.accounts.treasury_sol,
+        &ctx.accounts.system_program,
+    )?;
+    // Pools count against their mint's treasury until closed or cancelled
+    ctx.accounts.protocol_settings.register_pool(group_account.token_mint, group_account.config.is_native_sol)?;
+
     // Add creator as the first member
     group_account.member_addresses.push(ctx.accounts.creator.key());
     
     msg!("✅ SOL Pool created with UUID: {:?}", uuid);
+    msg!("💸 Create pool fee: {} lamports", pool_fee);
     msg!("👥 Max participants: {}", pool_config.max_participants);
     msg!("💰 Main vault: {}", ctx.accounts.vault_sol.key());
     msg!("🔒 Collateral vault: {}", ctx.accounts.collateral_vault.key());
@@ -596,7 +635,7 @@ pub fn create_sol_pool(
 
 //CREATE SPL POOL
 #[derive(Accounts)]
-#[instruction(pool_config: PoolConfig, uuid: [u8; 6])]
+#[instruction(pool_config: PoolConfig, uuid: [u8; 6], whitelist: Option<Vec<Pubkey>>)]
 pub struct CreateSplPool<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -604,28 +643,90 @@ pub struct CreateSplPool<'info> {
     #[account(
         init,
         payer = creator,
-        space = 8 + std::mem::size_of::<GroupAccount>(),
+        space = GroupAccount::space(whitelist.as_ref().map_or(0, |w| w.len())),
         seeds = [POOL_SEED, uuid.as_ref()],
         bump,
     )]
     pub group_account: Account<'info, GroupAccount>,
     
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         init,
         payer = creator,
         token::mint = token_mint,
         token::authority = group_account,
+        token::token_program = token_program,
         seeds = [VAULT_SPL_SEED, group_account.key().as_ref()],
         bump,
     )]
-    pub vault_spl: Account<'info, TokenAccount>,
+    pub vault_spl: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = creator,
+        token::mint = token_mint,
+        token::authority = group_account,
+        token::token_program = token_program,
+        seeds = [COLLATERAL_VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_spl: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = creator,
+        // One bid slot per participant
+        space = BidState::space(pool_config.max_participants as usize),
+        seeds = [BID_STATE_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub current_bid_state: Account<'info, BidState>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MemberAccount::INIT_SPACE,
+        seeds = [MEMBER_SEED, group_account.key().as_ref(), creator.key().as_ref()],
+        bump,
+    )]
+    pub member_account: Account<'info, MemberAccount>,
     
-    #[account(seeds = [PROTOCOL_SEED], bump = protocol_settings.bump)]
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_CREATE_POOL) @ HuiFiError::ProtocolPaused,
+    )]
     pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    // Pays the create pool fee in the pool's mint, can be omitted in wSOL pools
+    // (the fee is wrapped from lamports) or when no fee is due
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == creator.key() @ HuiFiError::InvalidTokenAccountOwner,
+        constraint = creator_token_account.mint == token_mint.key() @ HuiFiError::InvalidTokenMint,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Treasury registered for the pool's mint, only needed when a fee is due
+    #[account(
+        mut,
+        constraint = protocol_treasury.mint == token_mint.key() @ HuiFiError::InvalidTreasuryAccount,
+    )]
+    pub protocol_treasury: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(seeds = [LIMITS_SEED], bump = protocol_limits.bump)]
+    pub protocol_limits: Account<'info, ProtocolLimits>,
+
+    // Only allowlisted mints can back a pool
+    #[account(
+        seeds = [MINT_CONFIG_SEED, token_mint.key().as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -636,10 +737,14 @@ pub fn create_spl_pool(
     uuid: [u8; 6],
     whitelist: Option<Vec<Pubkey>>,
 ) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
     // Validate pool configuration
-    validate_pool_config(&pool_config)?;
+    validate_pool_config(&pool_config, &ctx.accounts.protocol_limits.limits, &ctx.accounts.mint_config, current_timestamp)?;
+    let whitelist = whitelist.unwrap_or_default();
+    require!(whitelist.len() <= MAX_WHITELIST_SIZE, HuiFiError::WhitelistTooLarge);
+    // Re-check in case the mint's extensions changed since it was allowlisted
+    validate_mint_extensions(&ctx.accounts.token_mint.to_account_info())?;
     
-    let current_timestamp = Clock::get()?.unix_timestamp;
     let group_account = &mut ctx.accounts.group_account;
     let bump = ctx.bumps.group_account;
     let mut config = pool_config.clone();
@@ -649,7 +754,7 @@ pub fn create_spl_pool(
     // Initialize the group account
     group_account.uuid = uuid;
     group_account.creator = ctx.accounts.creator.key();
-    group_account.whitelist = whitelist.unwrap_or_default();
+    group_account.whitelist = whitelist;
     group_account.token_mint = ctx.accounts.token_mint.key();
     group_account.vault = ctx.accounts.vault_spl.key();
     group_account.config = config;
@@ -665,14 +770,62 @@ pub fn create_spl_pool(
     group_account.current_bid_amount = None;
     group_account.current_winner = None;
     group_account.bump = bump;
-    
+    // Lock in the fees members agree to when joining
+    group_account.protocol_fee_bps = ctx.accounts.protocol_settings.fee_bps;
+    group_account.penalty_bps = ctx.accounts.protocol_settings.penalty_bps;
+    group_account.version = GROUP_ACCOUNT_VERSION;
+    group_account.used_invite_nonces = [0; MAX_INVITE_NONCES / 8];
+    group_account.cycle_contributors = 0;
+
+    // Initialize current bid state
+    let current_bid_state = &mut ctx.accounts.current_bid_state;
+    current_bid_state.pool = group_account.key();
+    current_bid_state.cycle = 0;
+    current_bid_state.bids = Vec::new();
+    current_bid_state.winner = None;
+    current_bid_state.bump = ctx.bumps.current_bid_state;
+    current_bid_state.version = BID_STATE_VERSION;
+
+    // Initialize the creator's member account
+    let member_account = &mut ctx.accounts.member_account;
+    member_account.owner = ctx.accounts.creator.key();
+    member_account.pool = group_account.key();
+    member_account.contributions_made = 0;
+    member_account.status = MemberStatus::Active;
+    member_account.has_received_payout = false;
+    member_account.eligible_for_payout = false;
+    member_account.collateral_staked = 0;
+    member_account.has_deposited_collateral = false;
+    member_account.reputation_points = 0;
+    member_account.last_contribution_timestamp = 0;
+    member_account.total_contributions = 0;
+    member_account.last_bid_cycle = None;
+    member_account.last_contribution_cycle = None;
+    member_account.payout_amount = 0;
+    member_account.bump = ctx.bumps.member_account;
+    member_account.version = MEMBER_ACCOUNT_VERSION;
+
+    // Charge the create pool fee in the pool's mint
+    let pool_fee = charge_spl_create_pool_fee(
+        &mut ctx.accounts.protocol_settings,
+        &ctx.accounts.creator,
+        ctx.accounts.creator_token_account.as_ref(),
+        ctx.accounts.protocol_treasury.as_mut(),
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+        &ctx.accounts.system_program,
+    )?;
+    // Pools count against their mint's treasury until closed or cancelled
+    ctx.accounts.protocol_settings.register_pool(group_account.token_mint, group_account.config.is_native_sol)?;
 
     // Add creator as the first member
     group_account.member_addresses.push(ctx.accounts.creator.key());
     
     msg!("✅ SPL Token Pool created with UUID: {:?}", uuid);
+    msg!("💸 Create pool fee: {} tokens", pool_fee);
     msg!("👥 Max participants: {}", pool_config.max_participants);
     msg!("💰 Token mint: {}", ctx.accounts.token_mint.key());
+    msg!("🔒 Collateral vault: {}", ctx.accounts.collateral_vault_spl.key());
     
     Ok(())
 }
@@ -699,17 +852,28 @@ pub struct JoinSolPool<'info> {
     #[account(
         init,
         payer = user,
-        space = 8 + std::mem::size_of::<MemberAccount>(),
+        space = 8 + MemberAccount::INIT_SPACE,
         seeds = [MEMBER_SEED, group_account.key().as_ref(), user.key().as_ref()],
         bump,
     )]
     pub member_account: Account<'info, MemberAccount>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_JOIN_POOL) @ HuiFiError::ProtocolPaused,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    /// CHECK: Instructions sysvar, only needed to verify an invite ticket
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
     
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn join_sol_pool(ctx: Context<JoinSolPool>, uuid: [u8; 6]) -> Result<()> {
+pub fn join_sol_pool(ctx: Context<JoinSolPool>, _uuid: [u8; 6], ticket: Option<InviteTicket>) -> Result<()> {
     let group_account = &mut ctx.accounts.group_account;
     let member_account = &mut ctx.accounts.member_account;
     let user_key = ctx.accounts.user.key();
@@ -720,13 +884,19 @@ pub fn join_sol_pool(ctx: Context<JoinSolPool>, uuid: [u8; 6]) -> Result<()> {
         HuiFiError::MemberAlreadyJoined
     );
     
-    // Check whitelist if applicable
-    if !group_account.whitelist.is_empty() {
-        require!(
-            group_account.whitelist.contains(&user_key),
-            HuiFiError::NotWhitelisted
-        );
-    }
+    // Recruitment closes at the join deadline
+    require!(
+        !group_account.is_join_deadline_passed(Clock::get()?.unix_timestamp),
+        HuiFiError::JoinDeadlinePassed
+    );
+    
+    // Check whitelist or invite ticket
+    check_join_access(
+        group_account,
+        &user_key,
+        ticket,
+        ctx.accounts.instructions_sysvar.as_ref(),
+    )?;
     
     // Initialize the member account
     let bump = ctx.bumps.member_account;
@@ -742,10 +912,11 @@ pub fn join_sol_pool(ctx: Context<JoinSolPool>, uuid: [u8; 6]) -> Result<()> {
     member_account.last_contribution_timestamp = 0;
     member_account.total_contributions = 0;
     member_account.has_deposited_collateral = false;
-    member_account.has_bid = false;
-    member_account.has_contributed = false;
+    member_account.last_bid_cycle = None;
+    member_account.last_contribution_cycle = None;
     member_account.payout_amount = 0;
     member_account.bump = bump;
+    member_account.version = MEMBER_ACCOUNT_VERSION;
     
     // Add user to the pool's member list
     group_account.member_addresses.push(user_key);
@@ -790,25 +961,36 @@ pub struct JoinSplPool<'info> {
     #[account(
         init,
         payer = user,
-        space = 8 + std::mem::size_of::<MemberAccount>(),
+        space = 8 + MemberAccount::INIT_SPACE,
         seeds = [MEMBER_SEED, group_account.key().as_ref(), user.key().as_ref()],
         bump,
     )]
     pub member_account: Account<'info, MemberAccount>,
     
+    // Can be omitted in wSOL pools, where the balance check falls back to lamports
     #[account(
         constraint = user_token_account.owner == user.key() @ HuiFiError::InvalidTokenAccountOwner,
         constraint = user_token_account.mint == group_account.token_mint @ HuiFiError::InvalidPoolConfig,
-        constraint = user_token_account.amount >= group_account.config.contribution_amount @ HuiFiError::InsufficientContribution,
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = !protocol_settings.is_paused(PAUSE_JOIN_POOL) @ HuiFiError::ProtocolPaused,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    /// CHECK: Instructions sysvar, only needed to verify an invite ticket
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn join_spl_pool(ctx: Context<JoinSplPool>, uuid: [u8; 6]) -> Result<()> {
+pub fn join_spl_pool(ctx: Context<JoinSplPool>, _uuid: [u8; 6], ticket: Option<InviteTicket>) -> Result<()> {
     let group_account = &mut ctx.accounts.group_account;
     let member_account = &mut ctx.accounts.member_account;
     let user_key = ctx.accounts.user.key();
@@ -819,14 +1001,36 @@ pub fn join_spl_pool(ctx: Context<JoinSplPool>, uuid: [u8; 6]) -> Result<()> {
         HuiFiError::MemberAlreadyJoined
     );
     
-    // Check whitelist if applicable
-    if !group_account.whitelist.is_empty() {
-        require!(
-            group_account.whitelist.contains(&user_key),
-            HuiFiError::NotWhitelisted
-        );
+    // Recruitment closes at the join deadline
+    require!(
+        !group_account.is_join_deadline_passed(Clock::get()?.unix_timestamp),
+        HuiFiError::JoinDeadlinePassed
+    );
+    
+    // Check the user can cover the first contribution
+    let contribution_amount = group_account.config.contribution_amount;
+    match ctx.accounts.user_token_account.as_ref() {
+        Some(user_token_account) => require!(
+            user_token_account.amount >= contribution_amount,
+            HuiFiError::InsufficientContribution
+        ),
+        None => {
+            require!(group_account.is_wrapped_sol(), HuiFiError::MissingTokenAccount);
+            require!(
+                ctx.accounts.user.lamports() >= contribution_amount,
+                HuiFiError::InsufficientContribution
+            );
+        }
     }
     
+    // Check whitelist or invite ticket
+    check_join_access(
+        group_account,
+        &user_key,
+        ticket,
+        ctx.accounts.instructions_sysvar.as_ref(),
+    )?;
+    
     // Initialize the member account
     let bump = ctx.bumps.member_account;
     
@@ -839,9 +1043,13 @@ pub fn join_spl_pool(ctx: Context<JoinSplPool>, uuid: [u8; 6]) -> Result<()> {
     member_account.collateral_staked = 0;
     member_account.reputation_points = 0;
     member_account.last_contribution_timestamp = 0;
-    member_account.payout_amount = 0;
+    member_account.total_contributions = 0;
     member_account.has_deposited_collateral = false;
+    member_account.last_bid_cycle = None;
+    member_account.last_contribution_cycle = None;
+    member_account.payout_amount = 0;
     member_account.bump = bump;
+    member_account.version = MEMBER_ACCOUNT_VERSION;
     
     // Add user to the pool's member list
     group_account.member_addresses.push(user_key);
@@ -850,6 +1058,8 @@ pub fn join_spl_pool(ctx: Context<JoinSplPool>, uuid: [u8; 6]) -> Result<()> {
     if group_account.member_addresses.len() as u8 == group_account.total_cycles {
         group_account.status = PoolStatus::Active { phase: CyclePhase::Bidding };
         group_account.payout_order = group_account.member_addresses.clone();
+        group_account.last_cycle_timestamp = Clock::get()?.unix_timestamp;
+        msg!("🎮 Pool is now active and entering bidding phase");
     }
     
     msg!("User joined SPL pool: {}", user_key);
@@ -861,6 +1071,668 @@ pub fn join_spl_pool(ctx: Context<JoinSplPool>, uuid: [u8; 6]) -> Result<()> {
     Ok(())
 }
 
+// ==================== WHITELIST FUNCTIONS ====================
+
+//ADD TO WHITELIST
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct AddToWhitelist<'info> {
+    #[account(
+        mut,
+        constraint = creator.key() == group_account.creator @ HuiFiError::Unauthorized,
+    )]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = group_account.status == PoolStatus::Initializing @ HuiFiError::InvalidPoolStatus,
+        realloc = GroupAccount::space(group_account.whitelist.len() + 1),
+        realloc::payer = creator,
+        realloc::zero = false,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_to_whitelist(ctx: Context<AddToWhitelist>, _uuid: [u8; 6], wallet: Pubkey) -> Result<()> {
+    let group_account = &mut ctx.accounts.group_account;
+
+    require!(
+        group_account.whitelist.len() < MAX_WHITELIST_SIZE,
+        HuiFiError::WhitelistTooLarge
+    );
+    require!(
+        !group_account.whitelist.contains(&wallet),
+        HuiFiError::AlreadyWhitelisted
+    );
+
+    group_account.whitelist.push(wallet);
+
+    emit!(WhitelistUpdated {
+        pool: group_account.key(),
+        wallet,
+        added: true,
+        whitelist_len: group_account.whitelist.len() as u8,
+    });
+    msg!("✅ Added {} to whitelist", wallet);
+    Ok(())
+}
+
+//REMOVE FROM WHITELIST
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct RemoveFromWhitelist<'info> {
+    #[account(
+        mut,
+        constraint = creator.key() == group_account.creator @ HuiFiError::Unauthorized,
+    )]
+    pub creator: Signer<'info>,
+
+    // Shrinks by one slot, the freed rent goes back to the creator
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = group_account.status == PoolStatus::Initializing @ HuiFiError::InvalidPoolStatus,
+        realloc = GroupAccount::space(group_account.whitelist.len().saturating_sub(1)),
+        realloc::payer = creator,
+        realloc::zero = false,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn remove_from_whitelist(ctx: Context<RemoveFromWhitelist>, _uuid: [u8; 6], wallet: Pubkey) -> Result<()> {
+    let group_account = &mut ctx.accounts.group_account;
+
+    let index = group_account
+        .whitelist
+        .iter()
+        .position(|w| *w == wallet)
+        .ok_or(HuiFiError::NotWhitelisted)?;
+    require!(
+        !group_account.member_addresses.contains(&wallet),
+        HuiFiError::WhitelistedMemberJoined
+    );
+    // An empty whitelist means anyone can join a public pool
+    require!(
+        group_account.whitelist.len() > 1 || group_account.config.is_private,
+        HuiFiError::WhitelistCannotBeEmptied
+    );
+
+    group_account.whitelist.remove(index);
+
+    emit!(WhitelistUpdated {
+        pool: group_account.key(),
+        wallet,
+        added: false,
+        whitelist_len: group_account.whitelist.len() as u8,
+    });
+    msg!("🗑️ Removed {} from whitelist", wallet);
+    Ok(())
+}
+
+// ==================== START POOL FUNCTIONS ====================
+
+// Lets the creator start an unfilled pool once the quorum has joined
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct StartPool<'info> {
+    #[account(
+        mut,
+        constraint = creator.key() == group_account.creator @ HuiFiError::Unauthorized,
+    )]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = group_account.status == PoolStatus::Initializing @ HuiFiError::InvalidPoolStatus,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+
+    #[account(
+        mut,
+        seeds = [BID_STATE_SEED, group_account.key().as_ref()],
+        bump = bid_state.bump,
+    )]
+    pub bid_state: Account<'info, BidState>,
+}
+
+pub fn start_pool(ctx: Context<StartPool>, _uuid: [u8; 6]) -> Result<()> {
+    let group_account = &mut ctx.accounts.group_account;
+    let member_count = group_account.member_addresses.len();
+
+    require!(
+        member_count >= group_account.config.min_participants as usize,
+        HuiFiError::QuorumNotReached
+    );
+
+    // Run one cycle per member who actually joined, the pot follows from the contributions
+    group_account.total_cycles = member_count as u8;
+    group_account.payout_order = group_account.member_addresses.clone();
+    group_account.current_cycle = 0;
+    group_account.last_cycle_timestamp = Clock::get()?.unix_timestamp;
+    group_account.initialize_active_status()?;
+
+    // Shrink the bid state to the real member count and return the spare rent to the creator
+    let bid_state_info = ctx.accounts.bid_state.to_account_info();
+    let new_space = BidState::space(member_count);
+    if new_space < bid_state_info.data_len() {
+        bid_state_info.realloc(new_space, false)?;
+        let excess = bid_state_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(new_space));
+        if excess > 0 {
+            **bid_state_info.try_borrow_mut_lamports()? -= excess;
+            **ctx.accounts.creator.try_borrow_mut_lamports()? += excess;
+        }
+    }
+
+    emit!(PoolStarted {
+        pool: group_account.key(),
+        member_count: member_count as u8,
+        max_participants: group_account.config.max_participants,
+    });
+
+    msg!("🎮 Pool started early with {}/{} members",
+        member_count,
+        group_account.config.max_participants
+    );
+
+    Ok(())
+}
+
+// ==================== EXPIRE POOL FUNCTIONS ====================
+
+// Permissionless crank, anyone can expire a pool that missed its join deadline
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct ExpirePool<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = group_account.status == PoolStatus::Initializing @ HuiFiError::InvalidPoolStatus,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+}
+
+pub fn expire_pool(ctx: Context<ExpirePool>, _uuid: [u8; 6]) -> Result<()> {
+    let group_account = &mut ctx.accounts.group_account;
+
+    require!(
+        group_account.is_join_deadline_passed(Clock::get()?.unix_timestamp),
+        HuiFiError::JoinDeadlineNotReached
+    );
+
+    // Members reclaim rent and deposits through leave_pool, the creator through cancel_pool
+    group_account.status = PoolStatus::Expired;
+
+    emit!(PoolExpired {
+        pool: group_account.key(),
+        join_deadline: group_account.config.join_deadline,
+        member_count: group_account.member_addresses.len() as u8,
+    });
+
+    msg!("⌛ Pool expired with {}/{} members",
+        group_account.member_addresses.len(),
+        group_account.total_cycles
+    );
+
+    Ok(())
+}
+
+// ==================== LEAVE POOL FUNCTIONS ====================
+
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct LeavePool<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = matches!(group_account.status, PoolStatus::Initializing | PoolStatus::Cancelled | PoolStatus::Expired) @ HuiFiError::InvalidPoolStatus,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+
+    // Closed back to the member for a full rent refund
+    #[account(
+        mut,
+        close = user,
+        seeds = [MEMBER_SEED, group_account.key().as_ref(), user.key().as_ref()],
+        bump = member_account.bump,
+    )]
+    pub member_account: Account<'info, MemberAccount>,
+
+    // Vaults and token accounts are only needed when the member has something escrowed
+    /// CHECK: SOL pool vault PDA
+    #[account(
+        mut,
+        seeds = [VAULT_SOL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub vault_sol: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SOL pool collateral vault PDA
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SOL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_sol: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub vault_spl: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_spl: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ HuiFiError::InvalidTokenAccountOwner,
+        constraint = user_token_account.mint == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        constraint = token_mint.key() == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// `unwrap_wsol` closes user_token_account into the wallet, only for a wSOL account made for this exit
+pub fn leave_pool(ctx: Context<LeavePool>, _uuid: [u8; 6], unwrap_wsol: bool) -> Result<()> {
+    let group_account = &mut ctx.accounts.group_account;
+    let member_account = &ctx.accounts.member_account;
+    let user_key = ctx.accounts.user.key();
+    let group_key = group_account.key();
+
+    require!(
+        group_account.member_addresses.contains(&user_key),
+        HuiFiError::NotPoolMember
+    );
+
+    let contributions = member_account.total_contributions;
+    let collateral = member_account.collateral_staked;
+
+    // Refund anything already escrowed
+    if group_account.config.is_native_sol {
+        if contributions > 0 {
+            let vault_sol = ctx.accounts.vault_sol.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: vault_sol.to_account_info(),
+                        to: ctx.accounts.user.to_account_info(),
+                    },
+                    &[&[VAULT_SOL_SEED, group_key.as_ref(), &[ctx.bumps.vault_sol.unwrap()]]],
+                ),
+                contributions,
+            )?;
+        }
+        if collateral > 0 {
+            let collateral_vault_sol = ctx.accounts.collateral_vault_sol.as_ref().ok_or(HuiFiError::MissingCollateralVault)?;
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: collateral_vault_sol.to_account_info(),
+                        to: ctx.accounts.user.to_account_info(),
+                    },
+                    &[&[COLLATERAL_VAULT_SOL_SEED, group_key.as_ref(), &[ctx.bumps.collateral_vault_sol.unwrap()]]],
+                ),
+                collateral,
+            )?;
+        }
+    } else if contributions > 0 || collateral > 0 {
+        let user_token_account = ctx.accounts.user_token_account.as_mut().ok_or(HuiFiError::MissingTokenAccount)?;
+        let token_mint = ctx.accounts.token_mint.as_ref().ok_or(HuiFiError::MissingTokenMint)?;
+        let token_program = ctx.accounts.token_program.as_ref().ok_or(HuiFiError::MissingTokenProgram)?;
+        let group_seeds = &[POOL_SEED, group_account.uuid.as_ref(), &[group_account.bump]];
+
+        if contributions > 0 {
+            let vault_spl = ctx.accounts.vault_spl.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
+            transfer_tokens(
+                token_program,
+                vault_spl,
+                user_token_account,
+                token_mint,
+                group_account.to_account_info(),
+                &[group_seeds],
+                contributions,
+            )?;
+        }
+        if collateral > 0 {
+            let collateral_vault_spl = ctx.accounts.collateral_vault_spl.as_ref().ok_or(HuiFiError::MissingCollateralVault)?;
+            transfer_tokens(
+                token_program,
+                collateral_vault_spl,
+                user_token_account,
+                token_mint,
+                group_account.to_account_info(),
+                &[group_seeds],
+                collateral,
+            )?;
+        }
+        // wSOL pools unwrap on exit when asked to
+        if unwrap_wsol {
+            require!(group_account.is_wrapped_sol(), HuiFiError::InvalidPoolType);
+            unwrap_sol(
+                user_token_account,
+                ctx.accounts.user.to_account_info(),
+                token_program,
+            )?;
+        }
+    }
+
+    group_account.total_contributions = group_account.total_contributions.saturating_sub(contributions);
+    group_account.member_addresses.retain(|member| member != &user_key);
+
+    // The pool cannot go on without its creator
+    let cancelled = user_key == group_account.creator && group_account.status == PoolStatus::Initializing;
+    if cancelled {
+        group_account.status = PoolStatus::Cancelled;
+        emit!(PoolCancelled {
+            pool: group_key,
+            creator: user_key,
+        });
+        msg!("🛑 Creator left, pool cancelled");
+    }
+
+    emit!(MemberLeft {
+        pool: group_key,
+        member: user_key,
+        refunded_contributions: contributions,
+        refunded_collateral: collateral,
+    });
+
+    msg!("👋 User left pool: {}", user_key);
+    msg!("Current member count: {}/{}",
+        group_account.member_addresses.len(),
+        group_account.total_cycles
+    );
+
+    Ok(())
+}
+
+// ==================== CANCEL POOL FUNCTIONS ====================
+
+#[derive(Accounts)]
+#[instruction(uuid: [u8; 6])]
+pub struct CancelPool<'info> {
+    #[account(
+        mut,
+        constraint = creator.key() == group_account.creator @ HuiFiError::Unauthorized,
+    )]
+    pub creator: Signer<'info>,
+
+    // A creator who already left, or whose pool expired, can still finish the cleanup
+    #[account(
+        mut,
+        close = creator,
+        seeds = [POOL_SEED, uuid.as_ref()],
+        bump = group_account.bump,
+        constraint = matches!(group_account.status, PoolStatus::Initializing | PoolStatus::Cancelled | PoolStatus::Expired) @ HuiFiError::InvalidPoolStatus,
+    )]
+    pub group_account: Account<'info, GroupAccount>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [BID_STATE_SEED, group_account.key().as_ref()],
+        bump = bid_state.bump,
+    )]
+    pub bid_state: Account<'info, BidState>,
+
+    // SOL pools pass the SOL vaults, SPL pools the token vaults
+    /// CHECK: SOL pool vault PDA
+    #[account(
+        mut,
+        seeds = [VAULT_SOL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub vault_sol: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SOL pool collateral vault PDA
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SOL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_sol: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub vault_spl: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SPL_SEED, group_account.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault_spl: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Writable so withheld Token-2022 fees can be harvested before the vaults close
+    #[account(
+        mut,
+        constraint = token_mint.key() == group_account.token_mint @ HuiFiError::InvalidTokenMint,
+    )]
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    // Releases the pool's hold on its treasury
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Remaining accounts, one entry per member in `member_addresses` order:
+// SOL pools: [member_account, member_wallet]
+// SPL pools: [member_account, member_wallet, member_token_account]
+pub fn cancel_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelPool<'info>>,
+    _uuid: [u8; 6],
+) -> Result<()> {
+    let group_account = &ctx.accounts.group_account;
+    let group_key = group_account.key();
+    let is_native_sol = group_account.config.is_native_sol;
+    let group_seeds = &[POOL_SEED, group_account.uuid.as_ref(), &[group_account.bump]];
+
+    let stride = if is_native_sol { 2 } else { 3 };
+    require!(
+        ctx.remaining_accounts.len() == group_account.member_addresses.len() * stride,
+        HuiFiError::InvalidRemainingAccounts
+    );
+
+    let mut refunded: u64 = 0;
+    for (member_key, accounts) in group_account
+        .member_addresses
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(stride))
+    {
+        let member_account = Account::<MemberAccount>::try_from(&accounts[0])?;
+        let member_wallet = &accounts[1];
+        require_keys_eq!(member_wallet.key(), *member_key, HuiFiError::NotPoolMember);
+        require!(
+            member_account.pool == group_key && member_account.owner == *member_key,
+            HuiFiError::NotPoolMember
+        );
+
+        let contributions = member_account.total_contributions;
+        let collateral = member_account.collateral_staked;
+
+        // Refund deposits
+        if is_native_sol {
+            if contributions > 0 {
+                let vault_sol = ctx.accounts.vault_sol.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: vault_sol.to_account_info(),
+                            to: member_wallet.clone(),
+                        },
+                        &[&[VAULT_SOL_SEED, group_key.as_ref(), &[ctx.bumps.vault_sol.unwrap()]]],
+                    ),
+                    contributions,
+                )?;
+            }
+            if collateral > 0 {
+                let collateral_vault_sol = ctx.accounts.collateral_vault_sol.as_ref().ok_or(HuiFiError::MissingCollateralVault)?;
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: collateral_vault_sol.to_account_info(),
+                            to: member_wallet.clone(),
+                        },
+                        &[&[COLLATERAL_VAULT_SOL_SEED, group_key.as_ref(), &[ctx.bumps.collateral_vault_sol.unwrap()]]],
+                    ),
+                    collateral,
+                )?;
+            }
+        } else if contributions > 0 || collateral > 0 {
+            let mut member_token_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+            require_keys_eq!(member_token_account.owner, *member_key, HuiFiError::InvalidTokenAccountOwner);
+            require_keys_eq!(member_token_account.mint, group_account.token_mint, HuiFiError::InvalidTokenMint);
+            let token_mint = ctx.accounts.token_mint.as_ref().ok_or(HuiFiError::MissingTokenMint)?;
+            let token_program = ctx.accounts.token_program.as_ref().ok_or(HuiFiError::MissingTokenProgram)?;
+
+            if contributions > 0 {
+                let vault_spl = ctx.accounts.vault_spl.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
+                transfer_tokens(
+                    token_program,
+                    vault_spl,
+                    &mut member_token_account,
+                    token_mint,
+                    group_account.to_account_info(),
+                    &[group_seeds],
+                    contributions,
+                )?;
+            }
+            if collateral > 0 {
+                let collateral_vault_spl = ctx.accounts.collateral_vault_spl.as_ref().ok_or(HuiFiError::MissingCollateralVault)?;
+                transfer_tokens(
+                    token_program,
+                    collateral_vault_spl,
+                    &mut member_token_account,
+                    token_mint,
+                    group_account.to_account_info(),
+                    &[group_seeds],
+                    collateral,
+                )?;
+            }
+        }
+        refunded = refunded
+            .checked_add(contributions)
+            .and_then(|total| total.checked_add(collateral))
+            .ok_or(HuiFiError::Overflow)?;
+
+        // Rent goes back to whoever paid for the member account
+        member_account.close(member_wallet.clone())?;
+    }
+
+    // Close the vaults, their rent goes to the creator
+    if is_native_sol {
+        let vault_sol = ctx.accounts.vault_sol.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
+        let collateral_vault_sol = ctx.accounts.collateral_vault_sol.as_ref().ok_or(HuiFiError::MissingCollateralVault)?;
+        drain_sol_vault(
+            vault_sol,
+            &ctx.accounts.creator,
+            &ctx.accounts.system_program,
+            &[VAULT_SOL_SEED, group_key.as_ref(), &[ctx.bumps.vault_sol.unwrap()]],
+        )?;
+        drain_sol_vault(
+            collateral_vault_sol,
+            &ctx.accounts.creator,
+            &ctx.accounts.system_program,
+            &[COLLATERAL_VAULT_SOL_SEED, group_key.as_ref(), &[ctx.bumps.collateral_vault_sol.unwrap()]],
+        )?;
+    } else {
+        let vault_spl = ctx.accounts.vault_spl.as_ref().ok_or(HuiFiError::MissingPoolVault)?;
+        let collateral_vault_spl = ctx.accounts.collateral_vault_spl.as_ref().ok_or(HuiFiError::MissingCollateralVault)?;
+        let token_program = ctx.accounts.token_program.as_ref().ok_or(HuiFiError::MissingTokenProgram)?;
+        let token_mint = ctx.accounts.token_mint.as_ref().ok_or(HuiFiError::MissingTokenMint)?;
+        for vault in [vault_spl, collateral_vault_spl] {
+            close_token_account(
+                token_program,
+                vault,
+                token_mint,
+                ctx.accounts.creator.to_account_info(),
+                group_account.to_account_info(),
+                &[group_seeds],
+            )?;
+        }
+    }
+
+    ctx.accounts.protocol_settings.release_pool(group_account.token_mint, is_native_sol);
+
+    emit!(PoolCancelled {
+        pool: group_key,
+        creator: ctx.accounts.creator.key(),
+    });
+
+    msg!("🛑 Pool cancelled: {:?}", group_account.uuid);
+    msg!("💸 Refunded {} to {} members", refunded, group_account.member_addresses.len());
+
+    Ok(())
+}
+
+// Send every lamport in a system-owned vault PDA to `destination`
+fn drain_sol_vault<'info>(
+    vault: &UncheckedAccount<'info>,
+    destination: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    vault_seeds: &[&[u8]],
+) -> Result<()> {
+    let balance = vault.lamports();
+    if balance == 0 {
+        return Ok(());
+    }
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: vault.to_account_info(),
+                to: destination.to_account_info(),
+            },
+            &[vault_seeds],
+        ),
+        balance,
+    )
+}
+
 // // ==================== CONTRIBUTE FUNCTIONS ====================
 
 // #[derive(Accounts)]
@@ -1026,28 +1898,120 @@ pub fn join_spl_pool(ctx: Context<JoinSplPool>, uuid: [u8; 6]) -> Result<()> {
 
 // ==================== HELPER FUNCTIONS ====================
 
-fn validate_pool_config(config: &PoolConfig) -> Result<()> {
-    // Check number of participants
+// Moves the create pool fee (in lamports) from the creator to the SOL treasury, for SOL pools.
+// Returns the amount charged, which is 0 for fee exempt creators.
+fn charge_create_pool_fee<'info>(
+    protocol_settings: &mut Account<'info, ProtocolSettings>,
+    creator: &Signer<'info>,
+    treasury_sol: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<u64> {
+    let fee = protocol_settings.create_pool_fee;
+    if fee == 0 || protocol_settings.is_fee_exempt(&creator.key()) {
+        return Ok(0);
+    }
+
+    let treasury = protocol_settings
+        .find_treasury(None)
+        .ok_or(HuiFiError::MissingTreasury)?;
     require!(
-        config.max_participants >= MIN_PARTICIPANTS && config.max_participants <= MAX_PARTICIPANTS,
-        HuiFiError::InvalidPoolConfig
+        treasury.treasury == treasury_sol.key(),
+        HuiFiError::InvalidTreasuryAccount
     );
-    
-    // Check contribution amount
+
+    invoke(
+        &system_instruction::transfer(
+            &creator.key(),
+            &treasury_sol.key(),
+            fee,
+        ),
+        &[
+            creator.to_account_info(),
+            treasury_sol.to_account_info(),
+            system_program.to_account_info(),
+        ],
+    )?;
+    let native_mint = anchor_spl::token::spl_token::native_mint::id();
+    protocol_settings.record_fee(native_mint, true, fee)?;
+
+    emit!(CreatePoolFeeCharged {
+        creator: creator.key(),
+        token_mint: native_mint,
+        amount: fee,
+    });
+    Ok(fee)
+}
+
+// Moves the create pool fee set for the pool's mint from the creator to that mint's
+// treasury. Returns the amount the treasury received, which is 0 for fee exempt creators.
+fn charge_spl_create_pool_fee<'info>(
+    protocol_settings: &mut Account<'info, ProtocolSettings>,
+    creator: &Signer<'info>,
+    creator_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    protocol_treasury: Option<&mut InterfaceAccount<'info, TokenAccount>>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, System>,
+) -> Result<u64> {
+    let mint = token_mint.key();
+    let treasury = protocol_settings
+        .find_treasury(Some(mint))
+        .ok_or(HuiFiError::UnsupportedToken)?;
+    let fee = treasury.create_pool_fee;
+    if fee == 0 || protocol_settings.is_fee_exempt(&creator.key()) {
+        return Ok(0);
+    }
+
+    let protocol_treasury = protocol_treasury.ok_or(HuiFiError::MissingTreasury)?;
+    require!(
+        treasury.treasury == protocol_treasury.key(),
+        HuiFiError::InvalidTreasuryAccount
+    );
+
+    let received = match creator_token_account {
+        Some(creator_token_account) => transfer_tokens(
+            token_program,
+            creator_token_account,
+            protocol_treasury,
+            token_mint,
+            creator.to_account_info(),
+            &[],
+            fee,
+        )?,
+        None => {
+            require!(mint == anchor_spl::token::spl_token::native_mint::id(), HuiFiError::MissingTokenAccount);
+            wrap_sol(creator.to_account_info(), protocol_treasury, token_program, system_program, fee)?
+        }
+    };
+    protocol_settings.record_fee(mint, false, received)?;
+
+    emit!(CreatePoolFeeCharged {
+        creator: creator.key(),
+        token_mint: mint,
+        amount: received,
+    });
+    Ok(received)
+}
+
+fn validate_pool_config(config: &PoolConfig, limits: &LimitsConfig, mint_config: &MintConfig, now: i64) -> Result<()> {
+    // Check number of participants
     require!(
-        config.contribution_amount >= MIN_CONTRIBUTION_AMOUNT && config.contribution_amount <= MAX_CONTRIBUTION_AMOUNT,
+        config.max_participants >= limits.min_participants && config.max_participants <= limits.max_participants,
         HuiFiError::InvalidPoolConfig
     );
     
+    // Check contribution amount against the mint's range
+    mint_config.validate_contribution(config.contribution_amount)?;
+    
     // Check cycle duration
     require!(
-        config.cycle_duration_seconds >= MIN_CYCLE_DURATION && config.cycle_duration_seconds <= MAX_CYCLE_DURATION,
+        config.cycle_duration_seconds >= limits.min_cycle_duration && config.cycle_duration_seconds <= limits.max_cycle_duration,
         HuiFiError::InvalidPoolConfig
     );
     
     // Check payout delay
     require!(
-        config.payout_delay_seconds >= MIN_PAYOUT_DELAY && config.payout_delay_seconds <= MAX_PAYOUT_DELAY,
+        config.payout_delay_seconds >= limits.min_payout_delay && config.payout_delay_seconds <= limits.max_payout_delay,
         HuiFiError::InvalidPoolConfig
     );
     
@@ -1059,10 +2023,71 @@ fn validate_pool_config(config: &PoolConfig) -> Result<()> {
     
     // Check collateral requirement
     require!(
-        config.collateral_requirement_bps >= MIN_COLLATERAL_REQUIREMENT_BPS,
+        config.collateral_requirement_bps >= limits.min_collateral_requirement_bps,
+        HuiFiError::InvalidPoolConfig
+    );
+    
+    // Check the quorum for an early start
+    require!(
+        config.min_participants >= limits.min_participants && config.min_participants <= config.max_participants,
         HuiFiError::InvalidPoolConfig
     );
     
+    // Check bid bounds, a full discount would leave nothing to contribute
+    require!(
+        config.min_discount_bps <= config.max_discount_bps
+            && (config.max_discount_bps as u64) < BASIS_POINTS_DIVISOR,
+        HuiFiError::InvalidPoolConfig
+    );
+    
+    // Recruitment has to stay open for some time
+    require!(
+        config.join_deadline > now,
+        HuiFiError::InvalidJoinDeadline
+    );
+    
     Ok(())
 }
 
+#[event]
+pub struct CreatePoolFeeCharged {
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MemberLeft {
+    pub pool: Pubkey,
+    pub member: Pubkey,
+    pub refunded_contributions: u64,
+    pub refunded_collateral: u64,
+}
+
+#[event]
+pub struct WhitelistUpdated {
+    pub pool: Pubkey,
+    pub wallet: Pubkey,
+    pub added: bool,
+    pub whitelist_len: u8,
+}
+
+#[event]
+pub struct PoolStarted {
+    pub pool: Pubkey,
+    pub member_count: u8,
+    pub max_participants: u8,
+}
+
+#[event]
+pub struct PoolExpired {
+    pub pool: Pubkey,
+    pub join_deadline: i64,
+    pub member_count: u8,
+}
+
+#[event]
+pub struct PoolCancelled {
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+}
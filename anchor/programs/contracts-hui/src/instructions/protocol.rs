@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint};
+use anchor_lang::system_program;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, CloseAccount};
 
-use crate::state::{ProtocolSettings, TreasuryAccount};
+use crate::state::{ProtocolSettings, TreasuryAccount, Role, RoleMember, ProtocolLimits, LimitsConfig, MintConfig};
 use crate::constants::*;
 use crate::errors::*;
+use crate::instructions::token_utils::{transfer_tokens, validate_mint_extensions};
 
 #[derive(Accounts)]
 pub struct InitializeProtocol<'info> {
@@ -13,8 +16,7 @@ pub struct InitializeProtocol<'info> {
     #[account(
         init,
         payer = admin,
-        // space = 8 + std::mem::size_of::<ProtocolSettings>(),
-        space = 8 + 5000, // Estimate to hold protocol + ~50 treasury entries
+        space = 8 + ProtocolSettings::INIT_SPACE,
         seeds = [PROTOCOL_SEED],
         bump,
     )]
@@ -87,9 +89,13 @@ pub fn initialize_protocol(
 ) -> Result<()> {
     // Validate the protocol fee (maximum 10%)
     require!(
-        protocol_fee_bps <= 1000,
+        protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS,
         HuiFiError::InvalidPoolConfig
     );
+    require!(
+        penalty_bps <= MAX_PENALTY_BPS && create_pool_fee <= MAX_CREATE_POOL_FEE,
+        HuiFiError::FeeTooHigh
+    );
     
     let protocol_settings = &mut ctx.accounts.protocol_settings;
     let bump = ctx.bumps.protocol_settings;
@@ -103,6 +109,11 @@ pub fn initialize_protocol(
     protocol_settings.yield_generated = 0;
     protocol_settings.reserve_buffer = 0;
     protocol_settings.bump = bump;
+    protocol_settings.pending_admin = None;
+    protocol_settings.fee_exempt_creators = Vec::new();
+    protocol_settings.paused_flags = 0;
+    protocol_settings.role_members = Vec::new();
+    protocol_settings.version = PROTOCOL_SETTINGS_VERSION;
     
     protocol_settings.treasury_accounts = vec![
         TreasuryAccount {
@@ -110,6 +121,8 @@ pub fn initialize_protocol(
             treasury: ctx.accounts.treasury_sol.key(),
             total_collected: 0,
             is_native_sol: true,
+            open_pools: 0,
+            create_pool_fee: 0,
         },
         // TreasuryAccount {
         //     token_mint: Some(ctx.accounts.mint_usdc.key()),
@@ -135,138 +148,1036 @@ pub fn initialize_protocol(
     Ok(())
 }
 
-// //UPDATE PROTOCOL TREASURY ACCOUNT SPL TOKEN
-// #[derive(Accounts)]
-// pub struct UpdateTreasuryAccount<'info>{
-//     #[account(mut)]
-//     pub admin: Signer<'info>,
-//     #[account(
-//         mut,
-//         seeds = [PROTOCOL_SEED],
-//         bump = protocol_settings.bump,
-//         constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
-//     )]h
-//     pub protocol_settings: Account<'info, ProtocolSettings>,
-
-//     #[account(mut)]
-//     pub new_treasury_account: Account<'info, TokenAccount>,
-//     pub new_mint: Account<'info, Mint>,
-//     pub token_program: Program<'info, Token>,
-// }
-// pub fn update_treasury_account(ctx: Context<UpdateTreasuryAccount>)->Result<()>{
-//     let protocol = &mut ctx.accounts.protocol_settings;
-//     let mint = ctx.accounts.new_mint.key();
-
-//     //Validate new treasury account is not already in the protocol
-//     require!(
-//         !protocol.treasury_accounts.iter().any(|t|t.token_mint == Some(mint)),
-//         HuiFiError::TreasuryAccountAlreadyExists
-//     );
-//     //Validate new treasury account is a valid SPL token account
-//     require!(
-//         ctx.accounts.new_treasury_account.mint == mint,
-//         HuiFiError::InvalidTreasuryAccount
-//     );
-//     require!(
-//         *ctx.accounts.new_treasury_account.to_account_info().owner == ctx.accounts.token_program.key(),
-//         HuiFiError::InvalidTreasuryAccount
-//     );
-//     let new_entry = TreasuryAccount{
-//         token_mint: Some(mint),
-//         treasury: ctx.accounts.new_treasury_account.key(),
-//         total_collected: 0,
-//         is_native_sol: false,
-//     };
-//     protocol.treasury_accounts.push(new_entry);
-//     msg!("Treasury account updated for mint: {}", mint);
-//     Ok(())
-// }
-
-// //UPDATE PROTOCOL PAYOUT FEE
-// #[derive(Accounts)]
-// pub struct UpdateProtocolFee<'info> {
-//     #[account(mut)]
-//     pub admin: Signer<'info>,
-//     #[account(
-//         mut,
-//         seeds = [PROTOCOL_SEED],
-//         bump = protocol_settings.bump,
-//         constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
-//     )]
-//     pub protocol_settings: Account<'info, ProtocolSettings>,
-// }
-
-// pub fn update_protocol_fee(
-//     ctx: Context<UpdateProtocolFee>,
-//     new_fee_bps: u16,
-// ) -> Result<()> {
-//     // Validate the new fee (maximum 10%)
-//     require!(
-//         new_fee_bps <= 1000,
-//         HuiFiError::InvalidPoolConfig
-//     );
-//     let protocol_settings = &mut ctx.accounts.protocol_settings;
-//     protocol_settings.fee_bps = new_fee_bps;
-//     msg!("Protocol fee updated to: {}", new_fee_bps);
-//     Ok(())
-// }
-
-// //UPDATE PROTOCOL CREATE POOL FEE
-// #[derive(Accounts)]
-// pub struct UpdateCreatePoolFee<'info>{
-//     #[account(mut)]
-//     pub admin: Signer<'info>,
+// ==================== TREASURY REGISTRY ====================
+
+//ADD PROTOCOL TREASURY ACCOUNT SPL TOKEN
+#[derive(Accounts)]
+pub struct AddTreasury<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::TreasuryManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_mint,
+        token::authority = protocol_settings,
+        token::token_program = token_program,
+        seeds = [TREASURY_SEED, token_mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn add_treasury(ctx: Context<AddTreasury>) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_settings;
+    let mint = ctx.accounts.token_mint.key();
+
+    //Validate new treasury account is not already in the protocol
+    require!(
+        protocol.find_treasury(Some(mint)).is_none(),
+        HuiFiError::TreasuryAccountAlreadyExists
+    );
+    require!(
+        protocol.treasury_accounts.len() < MAX_TREASURY_ACCOUNTS,
+        HuiFiError::TreasuryRegistryFull
+    );
+
+    protocol.treasury_accounts.push(TreasuryAccount {
+        token_mint: Some(mint),
+        treasury: ctx.accounts.treasury.key(),
+        total_collected: 0,
+        is_native_sol: false,
+        open_pools: 0,
+        create_pool_fee: 0,
+    });
+
+    emit!(TreasuryAdded {
+        token_mint: mint,
+        treasury: ctx.accounts.treasury.key(),
+    });
+    msg!("Treasury account added for mint: {}", mint);
+    Ok(())
+}
+
+//REMOVE PROTOCOL TREASURY ACCOUNT SPL TOKEN
+#[derive(Accounts)]
+pub struct RemoveTreasury<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::TreasuryManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = protocol_settings,
+        token::token_program = token_program,
+        seeds = [TREASURY_SEED, token_mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn remove_treasury(ctx: Context<RemoveTreasury>) -> Result<()> {
+    let mint = ctx.accounts.token_mint.key();
+
+    // Fees must be withdrawn first so nothing is stranded in a closed account
+    require!(
+        ctx.accounts.treasury.amount == 0,
+        HuiFiError::TreasuryNotEmpty
+    );
+
+    let index = ctx.accounts.protocol_settings
+        .treasury_accounts
+        .iter()
+        .position(|t| t.token_mint == Some(mint))
+        .ok_or(HuiFiError::InvalidTreasuryAccount)?;
+
+    // Live pools in this mint pay fees and penalties into it
+    require!(
+        ctx.accounts.protocol_settings.treasury_accounts[index].open_pools == 0,
+        HuiFiError::TreasuryInUse
+    );
+
+    let bump = ctx.accounts.protocol_settings.bump;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.treasury.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.protocol_settings.to_account_info(),
+        },
+        &[&[PROTOCOL_SEED, &[bump]]],
+    ))?;
+
+    let protocol = &mut ctx.accounts.protocol_settings;
+    protocol.treasury_accounts.remove(index);
+
+    emit!(TreasuryRemoved {
+        token_mint: mint,
+        treasury: ctx.accounts.treasury.key(),
+    });
+    msg!("Treasury account removed for mint: {}", mint);
+    Ok(())
+}
+
+//WITHDRAW FROM PROTOCOL TREASURY (SOL OR SPL)
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::TreasuryManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    /// CHECK: SOL treasury PDA, checked against the registry (SOL withdrawals only)
+    #[account(mut)]
+    pub treasury_sol: Option<AccountInfo<'info>>,
+
+    /// CHECK: Receives the withdrawn SOL (SOL withdrawals only)
+    #[account(mut)]
+    pub destination_sol: Option<AccountInfo<'info>>,
+
+    // SPL treasury, checked against the registry (SPL withdrawals only)
+    #[account(mut)]
+    pub treasury_spl: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub destination_spl: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Treasury mint (SPL withdrawals only)
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, HuiFiError::InvalidWithdrawAmount);
+    let protocol = &ctx.accounts.protocol_settings;
+
+    if let Some(treasury) = ctx.accounts.treasury_spl.as_ref() {
+        let destination = ctx.accounts.destination_spl.as_mut().ok_or(HuiFiError::MissingDestination)?;
+        let token_program = ctx.accounts.token_program.as_ref().ok_or(HuiFiError::MissingTokenProgram)?;
+        let token_mint = ctx.accounts.token_mint.as_ref().ok_or(HuiFiError::MissingTokenMint)?;
+        let mint = treasury.mint;
+
+        let entry = protocol.find_treasury(Some(mint)).ok_or(HuiFiError::UnsupportedToken)?;
+        require!(entry.treasury == treasury.key(), HuiFiError::InvalidTreasuryAccount);
+        require!(token_mint.key() == mint, HuiFiError::InvalidTokenMint);
+        require!(destination.mint == mint, HuiFiError::InvalidTokenMint);
+        require!(amount <= treasury.amount, HuiFiError::InsufficientTreasuryFunds);
+
+        transfer_tokens(
+            token_program,
+            treasury,
+            destination,
+            token_mint,
+            protocol.to_account_info(),
+            &[&[PROTOCOL_SEED, &[protocol.bump]]],
+            amount,
+        )?;
+
+        emit!(TreasuryWithdrawn {
+            token_mint: Some(mint),
+            treasury: treasury.key(),
+            destination: destination.key(),
+            amount,
+            remaining: treasury.amount - amount,
+            total_collected: entry.total_collected,
+        });
+        msg!("Withdrew {} tokens of mint {} from treasury", amount, mint);
+    } else {
+        let treasury = ctx.accounts.treasury_sol.as_ref().ok_or(HuiFiError::MissingTreasury)?;
+        let destination = ctx.accounts.destination_sol.as_ref().ok_or(HuiFiError::MissingDestination)?;
+
+        let entry = protocol.find_treasury(None).ok_or(HuiFiError::UnsupportedToken)?;
+        require!(entry.treasury == treasury.key(), HuiFiError::InvalidTreasuryAccount);
+
+        // Keep the PDA rent-exempt and leave the reserve buffer untouched
+        let min_balance = Rent::get()?
+            .minimum_balance(0)
+            .saturating_add(protocol.reserve_buffer);
+        let available = treasury.lamports().saturating_sub(min_balance);
+        require!(amount <= available, HuiFiError::InsufficientTreasuryFunds);
+
+        let (_, treasury_bump) = Pubkey::find_program_address(&[TREASURY_SEED, b"sol"], ctx.program_id);
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: treasury.to_account_info(),
+                    to: destination.to_account_info(),
+                },
+                &[&[TREASURY_SEED, b"sol", &[treasury_bump]]],
+            ),
+            amount,
+        )?;
+
+        emit!(TreasuryWithdrawn {
+            token_mint: None,
+            treasury: treasury.key(),
+            destination: destination.key(),
+            amount,
+            remaining: treasury.lamports(),
+            total_collected: entry.total_collected,
+        });
+        msg!("Withdrew {} lamports from SOL treasury", amount);
+    }
+
+    Ok(())
+}
+
+//UPDATE SOL TREASURY RESERVE BUFFER
+#[derive(Accounts)]
+pub struct UpdateReserveBuffer<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::TreasuryManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+pub fn update_reserve_buffer(
+    ctx: Context<UpdateReserveBuffer>,
+    new_reserve_buffer: u64,
+) -> Result<()> {
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let old_reserve_buffer = protocol_settings.reserve_buffer;
+    protocol_settings.reserve_buffer = new_reserve_buffer;
+
+    emit!(ReserveBufferUpdated {
+        old_reserve_buffer,
+        new_reserve_buffer,
+    });
+    msg!("Reserve buffer updated from {} to {}", old_reserve_buffer, new_reserve_buffer);
+    Ok(())
+}
+
+//UPDATE PROTOCOL PAYOUT FEE
+#[derive(Accounts)]
+pub struct UpdateProtocolFee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::FeeManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+pub fn update_protocol_fee(
+    ctx: Context<UpdateProtocolFee>,
+    new_fee_bps: u16,
+) -> Result<()> {
+    // Validate the new fee (maximum 10%)
+    require!(
+        new_fee_bps <= MAX_PROTOCOL_FEE_BPS,
+        HuiFiError::FeeTooHigh
+    );
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let old_fee_bps = protocol_settings.fee_bps;
+    protocol_settings.fee_bps = new_fee_bps;
+
+    emit!(ProtocolFeeUpdated {
+        old_fee_bps,
+        new_fee_bps,
+    });
+    msg!("Protocol fee updated from {} to {}", old_fee_bps, new_fee_bps);
+    Ok(())
+}
+
+//UPDATE PROTOCOL CREATE POOL FEE
+#[derive(Accounts)]
+pub struct UpdateCreatePoolFee<'info>{
+    #[account(mut)]
+    pub authority: Signer<'info>,
     
-//     #[account(
-//         mut,
-//         seeds = [PROTOCOL_SEED],
-//         bump = protocol_settings.bump,
-//         constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
-//     )]
-//     pub protocol_settings: Account<'info, ProtocolSettings>,
-// }
-
-// pub fn update_create_pool_fee(
-//     ctx: Context<UpdateCreatePoolFee>,
-//     new_fee_lamports: u64,
-// ) -> Result<()> {
-//     //Validate new fee (maximum 10%)
-//     // require!(
-//     //     new_fee_bps <= 1000,
-//     //     HuiFiError::InvalidPoolConfig
-//     // );
-//     let protocol_settings = &mut ctx.accounts.protocol_settings;
-//     protocol_settings.create_pool_fee = new_fee_lamports;
-//     msg!("Create pool fee updated to: {}", new_fee_lamports);
-//     Ok(())
-// }
-
-// //UPDATE PROTOCOL PENALTY FEE
-// #[derive(Accounts)]
-// pub struct UpdatePenaltyFee<'info> {
-//     #[account(mut)]
-//     pub admin: Signer<'info>,
-
-//     #[account(
-//         mut,
-//         seeds = [PROTOCOL_SEED],
-//         bump = protocol_settings.bump,
-//         constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
-//     )]
-//     pub protocol_settings: Account<'info, ProtocolSettings>,
-// }
-
-// pub fn update_penalty_fee(
-//     ctx: Context<UpdatePenaltyFee>,
-//     new_penalty_bps: u16,
-// ) -> Result<()> {
-//     //Validate new penalty fee (maximum 10%)
-//     require!(
-//         new_penalty_bps <= 1000,
-//         HuiFiError::InvalidPoolConfig
-//     );
-//     let protocol_settings = &mut ctx.accounts.protocol_settings;
-//     protocol_settings.penalty_bps = new_penalty_bps;
-//     msg!("Penalty fee updated to: {}", new_penalty_bps);
-//     Ok(())
-// }
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::FeeManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+pub fn update_create_pool_fee(
+    ctx: Context<UpdateCreatePoolFee>,
+    new_fee_lamports: u64,
+) -> Result<()> {
+    // Validate new fee (maximum 1 SOL)
+    require!(
+        new_fee_lamports <= MAX_CREATE_POOL_FEE,
+        HuiFiError::FeeTooHigh
+    );
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let old_fee = protocol_settings.create_pool_fee;
+    protocol_settings.create_pool_fee = new_fee_lamports;
+
+    emit!(CreatePoolFeeUpdated {
+        old_fee,
+        new_fee: new_fee_lamports,
+    });
+    msg!("Create pool fee updated from {} to {}", old_fee, new_fee_lamports);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintCreatePoolFee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::FeeManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    // Only read for its decimals, the fee cap is set in whole tokens
+    pub token_mint: InterfaceAccount<'info, Mint>,
+}
+
+// SPL pools pay the create pool fee in their own mint, set per registered treasury
+pub fn update_mint_create_pool_fee(
+    ctx: Context<UpdateMintCreatePoolFee>,
+    new_fee: u64,
+) -> Result<()> {
+    let token_mint = ctx.accounts.token_mint.key();
+    let max_fee = 10u64
+        .checked_pow(ctx.accounts.token_mint.decimals as u32)
+        .and_then(|unit| unit.checked_mul(MAX_MINT_CREATE_POOL_FEE))
+        .unwrap_or(u64::MAX);
+    require!(new_fee <= max_fee, HuiFiError::FeeTooHigh);
+
+    let treasury = ctx.accounts.protocol_settings
+        .find_treasury_mut(Some(token_mint))
+        .ok_or(HuiFiError::MissingTreasury)?;
+    let old_fee = treasury.create_pool_fee;
+    treasury.create_pool_fee = new_fee;
+
+    emit!(MintCreatePoolFeeUpdated {
+        token_mint,
+        old_fee,
+        new_fee,
+    });
+    msg!("Create pool fee for {} updated from {} to {}", token_mint, old_fee, new_fee);
+    Ok(())
+}
+
+//UPDATE PROTOCOL PENALTY FEE
+#[derive(Accounts)]
+pub struct UpdatePenaltyFee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::FeeManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+pub fn update_penalty_fee(
+    ctx: Context<UpdatePenaltyFee>,
+    new_penalty_bps: u16,
+) -> Result<()> {
+    //Validate new penalty fee (maximum 10%)
+    require!(
+        new_penalty_bps <= MAX_PENALTY_BPS,
+        HuiFiError::FeeTooHigh
+    );
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let old_penalty_bps = protocol_settings.penalty_bps;
+    protocol_settings.penalty_bps = new_penalty_bps;
+
+    emit!(PenaltyFeeUpdated {
+        old_penalty_bps,
+        new_penalty_bps,
+    });
+    msg!("Penalty fee updated from {} to {}", old_penalty_bps, new_penalty_bps);
+    Ok(())
+}
+
+// ==================== CREATE POOL FEE EXEMPTIONS ====================
+
+#[derive(Accounts)]
+pub struct UpdateFeeExemption<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::FeeManager) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+pub fn add_fee_exempt_creator(
+    ctx: Context<UpdateFeeExemption>,
+    creator: Pubkey,
+) -> Result<()> {
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    require!(
+        !protocol_settings.is_fee_exempt(&creator),
+        HuiFiError::AlreadyFeeExempt
+    );
+    require!(
+        protocol_settings.fee_exempt_creators.len() < MAX_FEE_EXEMPT_CREATORS,
+        HuiFiError::FeeExemptListFull
+    );
+    protocol_settings.fee_exempt_creators.push(creator);
+
+    emit!(FeeExemptionUpdated {
+        creator,
+        is_exempt: true,
+    });
+    msg!("Create pool fee waived for: {}", creator);
+    Ok(())
+}
+
+pub fn remove_fee_exempt_creator(
+    ctx: Context<UpdateFeeExemption>,
+    creator: Pubkey,
+) -> Result<()> {
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let index = protocol_settings
+        .fee_exempt_creators
+        .iter()
+        .position(|c| *c == creator)
+        .ok_or(HuiFiError::NotFeeExempt)?;
+    protocol_settings.fee_exempt_creators.remove(index);
+
+    emit!(FeeExemptionUpdated {
+        creator,
+        is_exempt: false,
+    });
+    msg!("Create pool fee reinstated for: {}", creator);
+    Ok(())
+}
+
+// ==================== EMERGENCY PAUSE ====================
+
+#[derive(Accounts)]
+pub struct UpdatePause<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.has_role(&authority.key(), Role::Pauser) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+//PAUSE OPERATIONS
+pub fn pause(ctx: Context<UpdatePause>, flags: u16) -> Result<()> {
+    require!(
+        flags != 0 && flags & !PAUSE_ALL == 0,
+        HuiFiError::InvalidPauseFlags
+    );
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let old_flags = protocol_settings.paused_flags;
+    protocol_settings.paused_flags |= flags;
+
+    emit!(PauseFlagsUpdated {
+        old_flags,
+        new_flags: protocol_settings.paused_flags,
+    });
+    msg!("⏸️ Paused flags: {:#06b}", protocol_settings.paused_flags);
+    Ok(())
+}
+
+//UNPAUSE OPERATIONS
+pub fn unpause(ctx: Context<UpdatePause>, flags: u16) -> Result<()> {
+    require!(
+        flags != 0 && flags & !PAUSE_ALL == 0,
+        HuiFiError::InvalidPauseFlags
+    );
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let old_flags = protocol_settings.paused_flags;
+    protocol_settings.paused_flags &= !flags;
+
+    emit!(PauseFlagsUpdated {
+        old_flags,
+        new_flags: protocol_settings.paused_flags,
+    });
+    msg!("▶️ Paused flags: {:#06b}", protocol_settings.paused_flags);
+    Ok(())
+}
+
+// ==================== PROTOCOL LIMITS ====================
+
+//INITIALIZE PROTOCOL LIMITS
+#[derive(Accounts)]
+pub struct InitializeProtocolLimits<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProtocolLimits::INIT_SPACE,
+        seeds = [LIMITS_SEED],
+        bump,
+    )]
+    pub protocol_limits: Account<'info, ProtocolLimits>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_protocol_limits(ctx: Context<InitializeProtocolLimits>) -> Result<()> {
+    let protocol_limits = &mut ctx.accounts.protocol_limits;
+    // Start from the compile-time defaults
+    protocol_limits.limits = LimitsConfig::default();
+    protocol_limits.bump = ctx.bumps.protocol_limits;
+    protocol_limits.version = PROTOCOL_LIMITS_VERSION;
+
+    msg!("Protocol limits initialized with defaults");
+    Ok(())
+}
+
+//UPDATE PROTOCOL LIMITS
+#[derive(Accounts)]
+pub struct UpdateProtocolLimits<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    #[account(
+        mut,
+        seeds = [LIMITS_SEED],
+        bump = protocol_limits.bump,
+    )]
+    pub protocol_limits: Account<'info, ProtocolLimits>,
+}
+
+pub fn update_protocol_limits(
+    ctx: Context<UpdateProtocolLimits>,
+    new_limits: LimitsConfig,
+) -> Result<()> {
+    new_limits.validate()?;
+
+    let protocol_limits = &mut ctx.accounts.protocol_limits;
+    let old_limits = protocol_limits.limits;
+    protocol_limits.limits = new_limits;
+
+    emit!(ProtocolLimitsUpdated {
+        old_limits,
+        new_limits,
+    });
+    msg!("Protocol limits updated");
+    Ok(())
+}
+
+// ==================== SUPPORTED MINTS ====================
+
+//ADD SUPPORTED MINT
+#[derive(Accounts)]
+pub struct AddSupportedMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + MintConfig::INIT_SPACE,
+        seeds = [MINT_CONFIG_SEED, token_mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_supported_mint(
+    ctx: Context<AddSupportedMint>,
+    min_contribution: u64,
+    max_contribution: u64,
+) -> Result<()> {
+    validate_mint_extensions(&ctx.accounts.token_mint.to_account_info())?;
+
+    let mint_config = &mut ctx.accounts.mint_config;
+    mint_config.mint = ctx.accounts.token_mint.key();
+    mint_config.decimals = ctx.accounts.token_mint.decimals;
+    mint_config.min_contribution = min_contribution;
+    mint_config.max_contribution = max_contribution;
+    mint_config.bump = ctx.bumps.mint_config;
+    mint_config.version = MINT_CONFIG_VERSION;
+    validate_contribution_range(mint_config)?;
+
+    emit!(SupportedMintUpdated {
+        mint: mint_config.mint,
+        decimals: mint_config.decimals,
+        min_contribution,
+        max_contribution,
+        supported: true,
+    });
+    msg!("✅ Mint {} supported ({} decimals)", mint_config.mint, mint_config.decimals);
+    Ok(())
+}
+
+//UPDATE SUPPORTED MINT
+#[derive(Accounts)]
+pub struct UpdateSupportedMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    #[account(
+        mut,
+        seeds = [MINT_CONFIG_SEED, mint_config.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+pub fn update_supported_mint(
+    ctx: Context<UpdateSupportedMint>,
+    min_contribution: u64,
+    max_contribution: u64,
+) -> Result<()> {
+    let mint_config = &mut ctx.accounts.mint_config;
+    mint_config.min_contribution = min_contribution;
+    mint_config.max_contribution = max_contribution;
+    validate_contribution_range(mint_config)?;
+
+    emit!(SupportedMintUpdated {
+        mint: mint_config.mint,
+        decimals: mint_config.decimals,
+        min_contribution,
+        max_contribution,
+        supported: true,
+    });
+    msg!("Contribution range for {} set to {} - {}", mint_config.mint, min_contribution, max_contribution);
+    Ok(())
+}
+
+//REMOVE SUPPORTED MINT
+#[derive(Accounts)]
+pub struct RemoveSupportedMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [MINT_CONFIG_SEED, mint_config.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+pub fn remove_supported_mint(ctx: Context<RemoveSupportedMint>) -> Result<()> {
+    let mint_config = &ctx.accounts.mint_config;
+
+    // Existing pools keep working, only new pools are blocked
+    emit!(SupportedMintUpdated {
+        mint: mint_config.mint,
+        decimals: mint_config.decimals,
+        min_contribution: 0,
+        max_contribution: 0,
+        supported: false,
+    });
+    msg!("❌ Mint {} removed from allowlist", mint_config.mint);
+    Ok(())
+}
+
+fn validate_contribution_range(mint_config: &MintConfig) -> Result<()> {
+    require!(
+        mint_config.min_contribution > 0
+            && mint_config.min_contribution <= mint_config.max_contribution,
+        HuiFiError::InvalidContributionRange
+    );
+    // Make sure the upper bound is representable in base units
+    mint_config.to_base_units(mint_config.max_contribution)?;
+    Ok(())
+}
+
+// ==================== ROLES ====================
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+//GRANT ROLE
+pub fn grant_role(
+    ctx: Context<UpdateRole>,
+    account: Pubkey,
+    role: Role,
+) -> Result<()> {
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    require!(account != Pubkey::default(), HuiFiError::InvalidAdmin);
+
+    match protocol_settings.role_members.iter_mut().find(|m| m.account == account) {
+        Some(member) => {
+            require!(member.roles & role.flag() == 0, HuiFiError::RoleAlreadyGranted);
+            member.roles |= role.flag();
+        }
+        None => {
+            require!(
+                protocol_settings.role_members.len() < MAX_ROLE_MEMBERS,
+                HuiFiError::RoleMembersFull
+            );
+            protocol_settings.role_members.push(RoleMember {
+                account,
+                roles: role.flag(),
+            });
+        }
+    }
+
+    emit!(RoleUpdated {
+        account,
+        role,
+        granted: true,
+    });
+    msg!("Role {:?} granted to: {}", role, account);
+    Ok(())
+}
+
+//REVOKE ROLE
+pub fn revoke_role(
+    ctx: Context<UpdateRole>,
+    account: Pubkey,
+    role: Role,
+) -> Result<()> {
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let index = protocol_settings
+        .role_members
+        .iter()
+        .position(|m| m.account == account && m.roles & role.flag() != 0)
+        .ok_or(HuiFiError::RoleNotGranted)?;
+
+    let member = &mut protocol_settings.role_members[index];
+    member.roles &= !role.flag();
+    // Drop the entry once it holds no roles so the list does not fill up
+    if member.roles == 0 {
+        protocol_settings.role_members.remove(index);
+    }
+
+    emit!(RoleUpdated {
+        account,
+        role,
+        granted: false,
+    });
+    msg!("Role {:?} revoked from: {}", role, account);
+    Ok(())
+}
+
+// ==================== ADMIN HANDOVER ====================
+
+//PROPOSE NEW ADMIN
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.admin == admin.key() @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+pub fn propose_admin(
+    ctx: Context<ProposeAdmin>,
+    new_admin: Pubkey,
+) -> Result<()> {
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    require!(
+        new_admin != Pubkey::default() && new_admin != protocol_settings.admin,
+        HuiFiError::InvalidAdmin
+    );
+
+    // Overwrites any earlier proposal; the current admin stays in charge until accepted
+    let old_pending_admin = protocol_settings.pending_admin;
+    protocol_settings.pending_admin = Some(new_admin);
+
+    emit!(AdminTransferProposed {
+        admin: protocol_settings.admin,
+        old_pending_admin,
+        new_pending_admin: Some(new_admin),
+    });
+    msg!("Admin transfer proposed to: {}", new_admin);
+    Ok(())
+}
+
+//CANCEL ADMIN PROPOSAL
+pub fn cancel_admin_transfer(ctx: Context<ProposeAdmin>) -> Result<()> {
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let old_pending_admin = protocol_settings.pending_admin;
+    require!(old_pending_admin.is_some(), HuiFiError::NoPendingAdmin);
+    protocol_settings.pending_admin = None;
+
+    emit!(AdminTransferProposed {
+        admin: protocol_settings.admin,
+        old_pending_admin,
+        new_pending_admin: None,
+    });
+    msg!("Admin transfer cancelled");
+    Ok(())
+}
+
+//ACCEPT ADMIN ROLE
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut)]
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_settings.bump,
+        constraint = protocol_settings.pending_admin.is_some() @ HuiFiError::NoPendingAdmin,
+        constraint = protocol_settings.pending_admin == Some(new_admin.key()) @ HuiFiError::Unauthorized
+    )]
+    pub protocol_settings: Account<'info, ProtocolSettings>,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let protocol_settings = &mut ctx.accounts.protocol_settings;
+    let old_admin = protocol_settings.admin;
+    let new_admin = ctx.accounts.new_admin.key();
+
+    protocol_settings.admin = new_admin;
+    protocol_settings.pending_admin = None;
+
+    emit!(AdminTransferred {
+        old_admin,
+        new_admin,
+    });
+    msg!("Admin transferred from {} to {}", old_admin, new_admin);
+    Ok(())
+}
+
+// ==================== EVENTS ====================
+
+#[event]
+pub struct TreasuryAdded {
+    pub token_mint: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct TreasuryRemoved {
+    pub token_mint: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub token_mint: Option<Pubkey>,
+    pub treasury: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+    pub total_collected: u64,
+}
+
+#[event]
+pub struct ReserveBufferUpdated {
+    pub old_reserve_buffer: u64,
+    pub new_reserve_buffer: u64,
+}
+
+#[event]
+pub struct ProtocolFeeUpdated {
+    pub old_fee_bps: u16,
+    pub new_fee_bps: u16,
+}
+
+#[event]
+pub struct CreatePoolFeeUpdated {
+    pub old_fee: u64,
+    pub new_fee: u64,
+}
+
+#[event]
+pub struct MintCreatePoolFeeUpdated {
+    pub token_mint: Pubkey,
+    pub old_fee: u64,
+    pub new_fee: u64,
+}
+
+#[event]
+pub struct PenaltyFeeUpdated {
+    pub old_penalty_bps: u16,
+    pub new_penalty_bps: u16,
+}
+
+#[event]
+pub struct FeeExemptionUpdated {
+    pub creator: Pubkey,
+    pub is_exempt: bool,
+}
+
+#[event]
+pub struct PauseFlagsUpdated {
+    pub old_flags: u16,
+    pub new_flags: u16,
+}
+
+#[event]
+pub struct ProtocolLimitsUpdated {
+    pub old_limits: LimitsConfig,
+    pub new_limits: LimitsConfig,
+}
+
+#[event]
+pub struct SupportedMintUpdated {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub min_contribution: u64,
+    pub max_contribution: u64,
+    pub supported: bool,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub account: Pubkey,
+    pub role: Role,
+    pub granted: bool,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub old_pending_admin: Option<Pubkey>,
+    pub new_pending_admin: Option<Pubkey>,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::TransferFeeAmount, transfer_hook::TransferHook, BaseStateWithExtensions,
+        ExtensionType, StateWithExtensions,
+    },
+};
+use anchor_spl::token_2022_extensions::transfer_fee::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint};
+use anchor_spl::token_interface::{self, CloseAccount, Mint, SyncNative, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::*;
+
+// ==================== TOKEN HELPERS ====================
+
+// Reject Token-2022 mints whose extensions could move or lock pool funds behind our back.
+// Anything not explicitly allowed here is refused.
+pub fn validate_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        match extension {
+            // Fees are handled by crediting the amount actually received
+            ExtensionType::TransferFeeConfig
+            // Display-only extensions, raw amounts are unaffected
+            | ExtensionType::InterestBearingConfig
+            | ExtensionType::MetadataPointer
+            | ExtensionType::TokenMetadata
+            | ExtensionType::GroupPointer
+            | ExtensionType::TokenGroup
+            | ExtensionType::GroupMemberPointer
+            | ExtensionType::TokenGroupMember => {}
+            // A hook is only safe if no program is set and nobody can set one later
+            ExtensionType::TransferHook => {
+                let hook = state.get_extension::<TransferHook>()?;
+                require!(
+                    Option::<Pubkey>::from(hook.program_id).is_none()
+                        && Option::<Pubkey>::from(hook.authority).is_none(),
+                    HuiFiError::UnsupportedMintExtension
+                );
+            }
+            // Permanent delegate, close authority, frozen default state, non-transferable,
+            // confidential transfers and anything newer
+            _ => return err!(HuiFiError::UnsupportedMintExtension),
+        }
+    }
+    Ok(())
+}
+
+// transfer_checked that returns what the destination was actually credited,
+// which is less than `amount` for mints with a transfer fee
+pub fn transfer_tokens<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<u64> {
+    let balance_before = to.amount;
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )?;
+
+    to.reload()?;
+    let received = to.amount
+        .checked_sub(balance_before)
+        .ok_or(HuiFiError::Overflow)?;
+    Ok(received)
+}
+
+// Wrap lamports straight into a wSOL vault and return the amount credited
+pub fn wrap_sol<'info>(
+    payer: AccountInfo<'info>,
+    vault: &mut InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<u64> {
+    let balance_before = vault.amount;
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: payer,
+                to: vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    token_interface::sync_native(CpiContext::new(
+        token_program.to_account_info(),
+        SyncNative {
+            account: vault.to_account_info(),
+        },
+    ))?;
+
+    vault.reload()?;
+    let received = vault.amount
+        .checked_sub(balance_before)
+        .ok_or(HuiFiError::Overflow)?;
+    Ok(received)
+}
+
+// Close an empty PDA-owned token account, sending its rent to `destination`.
+// Token-2022 refuses to close an account still holding withheld transfer fees,
+// so those are harvested to the mint (which needs to be writable) first.
+pub fn close_token_account<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if withheld_fees(token_account)? > 0 {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: token_program.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+            ),
+            vec![token_account.to_account_info()],
+        )?;
+    }
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: token_account.to_account_info(),
+            destination,
+            authority,
+        },
+        signer_seeds,
+    ))
+}
+
+// Transfer fees withheld in a Token-2022 account, zero for anything else
+fn withheld_fees(token_account: &InterfaceAccount<TokenAccount>) -> Result<u64> {
+    let account_info = token_account.to_account_info();
+    if *account_info.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+
+    let data = account_info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TransferFeeAmount>()
+        .map(|fee| u64::from(fee.withheld_amount))
+        .unwrap_or(0))
+}
+
+// Close the owner's wSOL account so its whole balance lands in the owner's wallet as SOL.
+// This takes the account with it, so callers only do it when the user opted in.
+pub fn unwrap_sol<'info>(
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    owner: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    token_interface::close_account(CpiContext::new(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: token_account.to_account_info(),
+            destination: owner.clone(),
+            authority: owner,
+        },
+    ))
+}
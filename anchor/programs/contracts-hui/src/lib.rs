@@ -1,5 +1,4 @@
 use anchor_lang::prelude::*;
-use errors::*;
 use instructions::*;
 use state::*;
 // External modules with no re-exports at all
@@ -33,6 +32,140 @@ pub mod contracts_hui {
         instructions::protocol::initialize_protocol(ctx, protocol_fee_bps, create_pool_fee, 500)
     }
 
+    //TREASURY REGISTRY
+    pub fn add_treasury(
+        ctx: Context<AddTreasury>
+    ) -> Result<()> {
+        instructions::protocol::add_treasury(ctx)
+    }
+    pub fn remove_treasury(
+        ctx: Context<RemoveTreasury>
+    ) -> Result<()> {
+        instructions::protocol::remove_treasury(ctx)
+    }
+    pub fn withdraw_treasury(
+        ctx: Context<WithdrawTreasury>,
+        amount: u64
+    ) -> Result<()> {
+        instructions::protocol::withdraw_treasury(ctx, amount)
+    }
+    pub fn update_reserve_buffer(
+        ctx: Context<UpdateReserveBuffer>,
+        new_reserve_buffer: u64
+    ) -> Result<()> {
+        instructions::protocol::update_reserve_buffer(ctx, new_reserve_buffer)
+    }
+
+    //PROTOCOL GOVERNANCE
+    pub fn update_protocol_fee(
+        ctx: Context<UpdateProtocolFee>,
+        new_fee_bps: u16
+    ) -> Result<()> {
+        instructions::protocol::update_protocol_fee(ctx, new_fee_bps)
+    }
+    pub fn update_create_pool_fee(
+        ctx: Context<UpdateCreatePoolFee>,
+        new_fee_lamports: u64
+    ) -> Result<()> {
+        instructions::protocol::update_create_pool_fee(ctx, new_fee_lamports)
+    }
+    pub fn update_mint_create_pool_fee(
+        ctx: Context<UpdateMintCreatePoolFee>,
+        new_fee: u64
+    ) -> Result<()> {
+        instructions::protocol::update_mint_create_pool_fee(ctx, new_fee)
+    }
+    pub fn update_penalty_fee(
+        ctx: Context<UpdatePenaltyFee>,
+        new_penalty_bps: u16
+    ) -> Result<()> {
+        instructions::protocol::update_penalty_fee(ctx, new_penalty_bps)
+    }
+    pub fn add_fee_exempt_creator(
+        ctx: Context<UpdateFeeExemption>,
+        creator: Pubkey
+    ) -> Result<()> {
+        instructions::protocol::add_fee_exempt_creator(ctx, creator)
+    }
+    pub fn remove_fee_exempt_creator(
+        ctx: Context<UpdateFeeExemption>,
+        creator: Pubkey
+    ) -> Result<()> {
+        instructions::protocol::remove_fee_exempt_creator(ctx, creator)
+    }
+    pub fn pause(
+        ctx: Context<UpdatePause>,
+        flags: u16
+    ) -> Result<()> {
+        instructions::protocol::pause(ctx, flags)
+    }
+    pub fn unpause(
+        ctx: Context<UpdatePause>,
+        flags: u16
+    ) -> Result<()> {
+        instructions::protocol::unpause(ctx, flags)
+    }
+    pub fn initialize_protocol_limits(
+        ctx: Context<InitializeProtocolLimits>
+    ) -> Result<()> {
+        instructions::protocol::initialize_protocol_limits(ctx)
+    }
+    pub fn update_protocol_limits(
+        ctx: Context<UpdateProtocolLimits>,
+        new_limits: LimitsConfig
+    ) -> Result<()> {
+        instructions::protocol::update_protocol_limits(ctx, new_limits)
+    }
+    pub fn add_supported_mint(
+        ctx: Context<AddSupportedMint>,
+        min_contribution: u64,
+        max_contribution: u64
+    ) -> Result<()> {
+        instructions::protocol::add_supported_mint(ctx, min_contribution, max_contribution)
+    }
+    pub fn update_supported_mint(
+        ctx: Context<UpdateSupportedMint>,
+        min_contribution: u64,
+        max_contribution: u64
+    ) -> Result<()> {
+        instructions::protocol::update_supported_mint(ctx, min_contribution, max_contribution)
+    }
+    pub fn remove_supported_mint(
+        ctx: Context<RemoveSupportedMint>
+    ) -> Result<()> {
+        instructions::protocol::remove_supported_mint(ctx)
+    }
+    pub fn grant_role(
+        ctx: Context<UpdateRole>,
+        account: Pubkey,
+        role: Role
+    ) -> Result<()> {
+        instructions::protocol::grant_role(ctx, account, role)
+    }
+    pub fn revoke_role(
+        ctx: Context<UpdateRole>,
+        account: Pubkey,
+        role: Role
+    ) -> Result<()> {
+        instructions::protocol::revoke_role(ctx, account, role)
+    }
+    pub fn propose_admin(
+        ctx: Context<ProposeAdmin>,
+        new_admin: Pubkey
+    ) -> Result<()> {
+        instructions::protocol::propose_admin(ctx, new_admin)
+    }
+    pub fn cancel_admin_transfer(
+        ctx: Context<ProposeAdmin>
+    ) -> Result<()> {
+        instructions::protocol::cancel_admin_transfer(ctx)
+    }
+    pub fn accept_admin(
+        ctx: Context<AcceptAdmin>
+    ) -> Result<()> {
+        instructions::protocol::accept_admin(ctx)
+    }
+
     //CREATE A NEW HUIFI POOL
     // pub fn create_pool(
     //     ctx: Context<CreatePool>, 
@@ -60,15 +193,56 @@ pub mod contracts_hui {
     }
     pub fn join_sol_pool(
         ctx: Context<JoinSolPool>,
-        uuid: [u8; 6]
+        uuid: [u8; 6],
+        ticket: Option<InviteTicket>
     ) -> Result<()> {
-        instructions::pool::join_sol_pool(ctx, uuid)
+        instructions::pool::join_sol_pool(ctx, uuid, ticket)
     }
     pub fn join_spl_pool(
         ctx: Context<JoinSplPool>,
+        uuid: [u8; 6],
+        ticket: Option<InviteTicket>
+    ) -> Result<()> {
+        instructions::pool::join_spl_pool(ctx, uuid, ticket)
+    }
+    pub fn add_to_whitelist(
+        ctx: Context<AddToWhitelist>,
+        uuid: [u8; 6],
+        wallet: Pubkey
+    ) -> Result<()> {
+        instructions::pool::add_to_whitelist(ctx, uuid, wallet)
+    }
+    pub fn remove_from_whitelist(
+        ctx: Context<RemoveFromWhitelist>,
+        uuid: [u8; 6],
+        wallet: Pubkey
+    ) -> Result<()> {
+        instructions::pool::remove_from_whitelist(ctx, uuid, wallet)
+    }
+    pub fn start_pool(
+        ctx: Context<StartPool>,
+        uuid: [u8; 6]
+    ) -> Result<()> {
+        instructions::pool::start_pool(ctx, uuid)
+    }
+    pub fn expire_pool(
+        ctx: Context<ExpirePool>,
+        uuid: [u8; 6]
+    ) -> Result<()> {
+        instructions::pool::expire_pool(ctx, uuid)
+    }
+    pub fn leave_pool(
+        ctx: Context<LeavePool>,
+        uuid: [u8; 6],
+        unwrap_wsol: bool
+    ) -> Result<()> {
+        instructions::pool::leave_pool(ctx, uuid, unwrap_wsol)
+    }
+    pub fn cancel_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelPool<'info>>,
         uuid: [u8; 6]
     ) -> Result<()> {
-        instructions::pool::join_spl_pool(ctx, uuid)
+        instructions::pool::cancel_pool(ctx, uuid)
     }
     pub fn contribute_sol(
         ctx: Context<ContributeSol>,
@@ -77,13 +251,13 @@ pub mod contracts_hui {
     ) -> Result<()> {
         instructions::contribution::contribute_sol(ctx, uuid, amount)
     }
-    // pub fn contribute_spl(
-    //     ctx: Context<ContributeSpl>,
-    //     uuid: [u8; 6],
-    //     amount: u64
-    // ) -> Result<()> {
-    //     instructions::contribution::contribute_spl(ctx, uuid, amount)
-    // }
+    pub fn contribute_spl(
+        ctx: Context<ContributeSpl>,
+        uuid: [u8; 6],
+        amount: u64
+    ) -> Result<()> {
+        instructions::contribution::contribute_spl(ctx, uuid, amount)
+    }
     pub fn deposit_sol_collateral(
         ctx: Context<DepositSolCollateral>,
         uuid: [u8; 6],
@@ -91,13 +265,13 @@ pub mod contracts_hui {
     ) -> Result<()> {
         instructions::collateral::deposit_sol_collateral(ctx, uuid, amount)
     }
-    // pub fn deposit_spl_collateral(
-    //     ctx: Context<DepositSplCollateral>,
-    //     uuid: [u8; 6],
-    //     amount: u64
-    // ) -> Result<()> {
-    //     instructions::collateral::deposit_spl_collateral(ctx, uuid, amount)
-    // }
+    pub fn deposit_spl_collateral(
+        ctx: Context<DepositSplCollateral>,
+        uuid: [u8; 6],
+        amount: u64
+    ) -> Result<()> {
+        instructions::collateral::deposit_spl_collateral(ctx, uuid, amount)
+    }
     pub fn slash_collateral(
         ctx: Context<SlashCollateral>,
         uuid: [u8; 6],
@@ -111,6 +285,13 @@ pub mod contracts_hui {
     ) -> Result<()> {
         instructions::collateral::withdraw_sol_collateral(ctx, uuid)
     }
+    pub fn withdraw_spl_collateral(
+        ctx: Context<WithdrawSplCollateral>,
+        uuid: [u8; 6],
+        unwrap_wsol: bool
+    ) -> Result<()> {
+        instructions::collateral::withdraw_spl_collateral(ctx, uuid, unwrap_wsol)
+    }
     
     pub fn process_payout(
         ctx: Context<ProcessPayout>,
@@ -118,15 +299,29 @@ pub mod contracts_hui {
     ) -> Result<()> {
         instructions::payout::process_payout(ctx, uuid)
     }
-    pub fn submit_bid(
-        ctx: Context<SubmitBid>,
-        bid_amount: u64
+    pub fn process_spl_payout(
+        ctx: Context<ProcessSplPayout>,
+        uuid: [u8; 6],
+        unwrap_wsol: bool
     ) -> Result<()> {
-        instructions::bidding::submit_bid(ctx, bid_amount)
+        instructions::payout::process_spl_payout(ctx, uuid, unwrap_wsol)
+    }
+    pub fn commit_bid(
+        ctx: Context<CommitBid>,
+        commitment: [u8; 32]
+    ) -> Result<()> {
+        instructions::bidding::commit_bid(ctx, commitment)
+    }
+    pub fn reveal_bid(
+        ctx: Context<RevealBid>,
+        bid_amount: u64,
+        salt: [u8; 32]
+    ) -> Result<()> {
+        instructions::bidding::reveal_bid(ctx, bid_amount, salt)
     }
     pub fn finalize_bidding(
         ctx: Context<FinalizeBidding>,
-        uuid: [u8; 6]
+        _uuid: [u8; 6]
     ) -> Result<()> {
         instructions::bidding::finalize_bidding(ctx)
     }
@@ -146,21 +341,34 @@ pub mod contracts_hui {
     ) -> Result<()> {
         instructions::cycle_management::force_advance_cycle(ctx)
     }
-    // Commenting out until withdraw structures are implemented
-    /*
-    pub fn withdraw_sol_collateral(
-        ctx: Context<WithdrawSolCollateral>,
+    pub fn close_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClosePool<'info>>,
         uuid: [u8; 6]
     ) -> Result<()> {
-        instructions::collateral::withdraw_sol_collateral(ctx, uuid)
-    }   
-    pub fn withdraw_spl_collateral(
-        ctx: Context<WithdrawSplCollateral>,
-        uuid: [u8; 6]
+        instructions::cycle_management::close_pool(ctx, uuid)
+    }
+
+    //ACCOUNT MIGRATION
+    pub fn migrate_protocol_settings(
+        ctx: Context<MigrateAccount>
+    ) -> Result<()> {
+        instructions::migration::migrate_protocol_settings(ctx)
+    }
+    pub fn migrate_group_account(
+        ctx: Context<MigrateAccount>
+    ) -> Result<()> {
+        instructions::migration::migrate_group_account(ctx)
+    }
+    pub fn migrate_member_account(
+        ctx: Context<MigrateMemberAccount>
+    ) -> Result<()> {
+        instructions::migration::migrate_member_account(ctx)
+    }
+    pub fn migrate_bid_state(
+        ctx: Context<MigrateAccount>
     ) -> Result<()> {
-        instructions::collateral::withdraw_spl_collateral(ctx, uuid)
+        instructions::migration::migrate_bid_state(ctx)
     }
-    */
     
 
     
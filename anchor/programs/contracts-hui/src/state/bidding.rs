@@ -1,32 +1,84 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 use crate::constants::*;
+use crate::errors::HuiFiError;
+use crate::state::pool::TieBreakRule;
 #[account]
+#[derive(InitSpace)]
 pub struct BidState {
     pub pool: Pubkey,               // The group/pool this bid belongs to
     pub cycle: u8,                  // Which cycle this bid is for
+    #[max_len(MAX_POOL_MEMBERS)]
     pub bids: Vec<BidEntry>,        // All bids submitted
     pub winner: Option<Pubkey>,     // Winner of the bidding round
     pub bump: u8,
+    pub version: u8,                // Account layout version
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct BidEntry {
     pub bidder: Pubkey,
-    pub amount: u64,
+    pub amount: u64,                // Zero until revealed
+    pub commitment: [u8; 32],       // hash(amount, salt, bidder)
+    pub revealed: bool,
+    pub committed_at: i64,          // Used by TieBreakRule::EarliestBid
+}
+impl BidEntry {
+    // Binding the bidder stops members from copying someone else's commitment
+    pub fn commitment_for(amount: u64, salt: &[u8; 32], bidder: &Pubkey) -> [u8; 32] {
+        hashv(&[&amount.to_le_bytes(), salt, bidder.as_ref()]).to_bytes()
+    }
 }
 impl BidState {
-    // Existing methods...
+    // Account size with room for `max_bids` bids
+    pub fn space(max_bids: usize) -> usize {
+        8 + Self::INIT_SPACE - BidEntry::INIT_SPACE * MAX_POOL_MEMBERS.saturating_sub(max_bids)
+    }
+
+    pub fn revealed_bids(&self) -> impl Iterator<Item = &BidEntry> {
+        self.bids.iter().filter(|bid| bid.revealed)
+    }
+
+    // A revealed bid already takes the maximum discount
+    pub fn should_finalize_bidding(&self, max_bid: u64) -> bool {
+        self.revealed_bids().any(|bid| bid.amount >= max_bid)
+    }
+
+    // Highest revealed bid and how many bids share its amount, ties settled by `rule`
+    pub fn resolve_winner(
+        &self,
+        rule: TieBreakRule,
+        members: &[Pubkey],
+        random_seed: Option<u64>,
+    ) -> Result<Option<(BidEntry, u8)>> {
+        let Some(top_amount) = self.get_winning_bid_amount() else {
+            return Ok(None);
+        };
+        let tied: Vec<&BidEntry> = self.revealed_bids()
+            .filter(|bid| bid.amount == top_amount)
+            .collect();
 
-    // Helper to check if bidding should be finalized
-    pub fn should_finalize_bidding(&self, total_members: u8) -> bool {
-        self.bids.len() as u8 >= total_members || 
-        self.bids.iter().any(|bid| bid.amount >= MAX_BID_AMOUNT)
+        // min_by_key keeps the first of equal keys, so commit order is the final fallback
+        let winner = match rule {
+            _ if tied.len() == 1 => tied[0],
+            TieBreakRule::EarliestBid => tied.iter()
+                .min_by_key(|bid| bid.committed_at)
+                .copied()
+                .unwrap(),
+            TieBreakRule::LowestJoinIndex => tied.iter()
+                .min_by_key(|bid| members.iter().position(|member| *member == bid.bidder).unwrap_or(usize::MAX))
+                .copied()
+                .unwrap(),
+            TieBreakRule::Random => {
+                let seed = random_seed.ok_or(HuiFiError::MissingRandomnessSource)?;
+                tied[(seed % tied.len() as u64) as usize]
+            }
+        };
+        Ok(Some((winner.clone(), tied.len() as u8)))
     }
 
     // Helper to get winning bid amount
     pub fn get_winning_bid_amount(&self) -> Option<u64> {
-        self.bids.iter()
-            .max_by_key(|bid| bid.amount)
-            .map(|bid| bid.amount)
+        self.revealed_bids().map(|bid| bid.amount).max()
     }
 }
\ No newline at end of file
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::HuiFiError;
+
+// Bounds that pool configurations are validated against
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct LimitsConfig {
+    pub min_participants: u8,
+    pub max_participants: u8,
+    pub min_cycle_duration: u64,       // seconds
+    pub max_cycle_duration: u64,       // seconds
+    pub min_payout_delay: u64,         // seconds
+    pub max_payout_delay: u64,         // seconds
+    pub min_collateral_requirement_bps: u16,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            min_participants: MIN_PARTICIPANTS,
+            max_participants: MAX_PARTICIPANTS,
+            min_cycle_duration: MIN_CYCLE_DURATION,
+            max_cycle_duration: MAX_CYCLE_DURATION,
+            min_payout_delay: MIN_PAYOUT_DELAY,
+            max_payout_delay: MAX_PAYOUT_DELAY,
+            min_collateral_requirement_bps: MIN_COLLATERAL_REQUIREMENT_BPS,
+        }
+    }
+}
+
+impl LimitsConfig {
+    // Sanity checks so an admin typo cannot make every pool config invalid
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_participants >= 2 && self.min_participants <= self.max_participants,
+            HuiFiError::InvalidProtocolLimits
+        );
+        // Pool accounts are only sized for MAX_POOL_MEMBERS
+        require!(
+            self.max_participants as usize <= MAX_POOL_MEMBERS,
+            HuiFiError::InvalidProtocolLimits
+        );
+        require!(
+            self.min_cycle_duration > 0 && self.min_cycle_duration <= self.max_cycle_duration,
+            HuiFiError::InvalidProtocolLimits
+        );
+        require!(
+            self.min_payout_delay <= self.max_payout_delay,
+            HuiFiError::InvalidProtocolLimits
+        );
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default, InitSpace)]
+pub struct ProtocolLimits {
+    pub limits: LimitsConfig,   // Current pool configuration bounds
+    pub bump: u8,               // PDA bump
+    pub version: u8,            // Account layout version
+}
+
+// Allowlist entry for a pool token, contribution bounds are in whole tokens
+#[account]
+#[derive(Default, InitSpace)]
+pub struct MintConfig {
+    pub mint: Pubkey,                   // Supported token mint (native mint for SOL pools)
+    pub decimals: u8,                   // Mint decimals
+    pub min_contribution: u64,          // Minimum contribution in whole tokens
+    pub max_contribution: u64,          // Maximum contribution in whole tokens
+    pub bump: u8,                       // PDA bump
+    pub version: u8,                    // Account layout version
+}
+
+impl MintConfig {
+    // Convert a whole-token amount into base units of the mint
+    pub fn to_base_units(&self, amount: u64) -> Result<u64> {
+        10u64
+            .checked_pow(self.decimals as u32)
+            .and_then(|factor| amount.checked_mul(factor))
+            .ok_or(HuiFiError::Overflow.into())
+    }
+
+    // Check a contribution amount given in base units against the allowed range
+    pub fn validate_contribution(&self, amount: u64) -> Result<()> {
+        let min = self.to_base_units(self.min_contribution)?;
+        let max = self.to_base_units(self.max_contribution)?;
+        require!(
+            amount >= min && amount <= max,
+            HuiFiError::ContributionOutOfRange
+        );
+        Ok(())
+    }
+}
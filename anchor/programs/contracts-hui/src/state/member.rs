@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
 pub enum MemberStatus {
+    #[default]
     Active,
     // ReceivedPayout,
     Late,
@@ -9,20 +10,14 @@ pub enum MemberStatus {
     Withdrawed,
 }
 
-impl Default for MemberStatus {
-    fn default() -> Self {
-        MemberStatus::Active
-    }
-}
-
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct MemberAccount {
     pub owner: Pubkey,                 // Member wallet address
     pub pool: Pubkey,                  // Associated pool
     pub contributions_made: u8,        // Number of contributions made
-    pub has_bid: bool,                 // Whether the member has bid
-    pub has_contributed: bool,         // Whether the member has contributed
+    pub last_bid_cycle: Option<u8>,    // Last cycle the member bid in
+    pub last_contribution_cycle: Option<u8>, // Last cycle the member contributed in
     pub status: MemberStatus,          // Member status
     pub has_received_payout: bool,            // ✅ NEW: Blocks double payout or re-bidding
     pub eligible_for_payout: bool,            // ✅ NEW: Only winner can withdraw
@@ -33,4 +28,14 @@ pub struct MemberAccount {
     pub has_deposited_collateral: bool, // Whether the member has deposited collateral
     pub payout_amount: u64,            // Amount of payout received
     pub bump: u8,                      // PDA bump
+    pub version: u8,                   // Account layout version
+}
+impl MemberAccount {
+    pub fn has_bid_in(&self, cycle: u8) -> bool {
+        self.last_bid_cycle == Some(cycle)
+    }
+
+    pub fn has_contributed_in(&self, cycle: u8) -> bool {
+        self.last_contribution_cycle == Some(cycle)
+    }
 }
\ No newline at end of file
@@ -2,8 +2,10 @@ pub mod protocol;
 pub mod pool;
 pub mod member;
 pub mod bidding;
+pub mod limits;
 
 pub use protocol::*;
 pub use pool::*;
 pub use member::*;
-pub use bidding::*;
\ No newline at end of file
+pub use bidding::*;
+pub use limits::*;
\ No newline at end of file
@@ -1,42 +1,65 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::HuiFiError;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
 pub enum PoolStatus {
+    #[default]
     Initializing,  // Pool is being set up, accepting members
     Active {
         phase: CyclePhase,
     },        // Pool is active and running cycles
     Completed,     // All cycles completed successfully
     Defaulted,     // Pool defaulted due to member violations
+    Cancelled,     // Pool cancelled before activation
+    Expired,       // Pool did not fill before its join deadline
 }
 
-impl Default for PoolStatus {
-    fn default() -> Self {
-        PoolStatus::Initializing
-    }
-}
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
 pub enum CyclePhase {
-    Bidding,      // Members submitting bids
+    Bidding,      // Members committing sealed bids
     Contributing, // Members contributing funds
     ReadyForPayout, // Winner can claim funds
+    Revealing,    // Members revealing their committed bids
 }
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
 pub enum YieldPlatform {
+    #[default]
     None,
     JitoSol,
     Kamino,
     // Add more platforms as needed
 }
 
-impl Default for YieldPlatform {
-    fn default() -> Self {
-        YieldPlatform::None
-    }
+// How each cycle's recipient is chosen
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Default)]
+pub enum PayoutMode {
+    #[default]
+    Auction,    // Sealed-bid auction for the pot
+    FixedOrder, // payout_order[current_cycle], no bidding
+    Lottery,    // Random draw among members who have not been paid yet
 }
 
-// Configuration for creating a new pool
+// How equal top bids are settled
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Default)]
+pub enum TieBreakRule {
+    #[default]
+    EarliestBid,     // First commit wins
+    LowestJoinIndex, // Earliest member to join wins
+    Random,          // Draw from SlotHashes
+}
+
+// Off-chain invite signed by the pool creator, redeemable once per nonce
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InviteTicket {
+    pub pool_uuid: [u8; 6],
+    pub invitee: Option<Pubkey>, // None lets anyone holding the ticket join
+    pub expires_at: i64,
+    pub nonce: u16,              // Below MAX_INVITE_NONCES
+}
+
+// Configuration for creating a new pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct PoolConfig {
     pub max_participants: u8,        // Maximum number of participants (3-10)
     pub contribution_amount: u64,     // Amount each member contributes per cycle
@@ -48,6 +71,12 @@ pub struct PoolConfig {
     pub is_private: bool, // Whether the pool is private
     pub is_native_sol: bool, // Whether the pool is native SOL
     pub feed_id: [u8; 32], // Price feed ID
+    pub join_deadline: i64, // Joins are rejected after this timestamp
+    pub min_participants: u8, // Quorum the creator needs to start the pool early
+    pub payout_mode: PayoutMode, // How each cycle's recipient is chosen
+    pub min_discount_bps: u16, // Smallest bid, in bps of the cycle's pot
+    pub max_discount_bps: u16, // Largest bid, in bps of the cycle's pot, ends the auction
+    pub tie_break_rule: TieBreakRule, // How equal top bids are settled
 }
 
 impl Default for PoolConfig {
@@ -56,30 +85,39 @@ impl Default for PoolConfig {
             max_participants: 3,
             contribution_amount: 100,
             cycle_duration_seconds: 3 * 24 * 60 * 60, // 3 days
-            payout_delay_seconds: 1 * 24 * 60 * 60,   // 1 day
+            payout_delay_seconds: 24 * 60 * 60,       // 1 day
             early_withdrawal_fee_bps: 200,            // 2%
             collateral_requirement_bps: 20000,        // 200%
             yield_strategy: YieldPlatform::None,
             is_private: false,
             is_native_sol: false,
             feed_id: [0; 32],
+            join_deadline: 0,
+            min_participants: 3,
+            payout_mode: PayoutMode::Auction,
+            min_discount_bps: 100,                    // 1%
+            max_discount_bps: 3000,                   // 30%
+            tie_break_rule: TieBreakRule::EarliestBid,
         }
     }
 }
 
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct GroupAccount {
     pub uuid: [u8; 6],                  // NEW: 6-character alphanumeric
+    #[max_len(MAX_WHITELIST_SIZE)]
     pub whitelist: Vec<Pubkey>,         // NEW: optional whitelist
     pub creator: Pubkey,                // Creator of the pool
     pub token_mint: Pubkey,             // Token used for the pool (SOL, USDC, etc.)
     pub vault: Pubkey,                  // Pool's token vault
     pub config: PoolConfig,             // Pool configuration
+    #[max_len(MAX_POOL_MEMBERS)]
     pub member_addresses: Vec<Pubkey>,  // Member addresses
-    pub payout_order: Vec<Pubkey>,      // Order of payouts
+    #[max_len(MAX_POOL_MEMBERS)]
+    pub payout_order: Vec<Pubkey>,      // Order of payouts, past recipients first outside auctions
     pub current_cycle: u8,              // Current cycle (0-indexed)
-    pub total_cycles: u8,               // Total cycles (equal to max_participants)
+    pub total_cycles: u8,               // Total cycles (max_participants, or the member count if started early)
     pub status: PoolStatus,             // Current status of the pool
     pub total_contributions: u64,       // Total contributions made
     pub unclaimed_payout: u64,          // Unclaimed payout
@@ -90,8 +128,18 @@ pub struct GroupAccount {
     pub current_bid_amount: Option<u64>, // Current bid amount
     pub final_contribution_amount: Option<u64>, // Final contribution amount
     pub bump: u8,                       // PDA bump
+    pub protocol_fee_bps: u16,          // Protocol fee snapshotted at pool creation
+    pub penalty_bps: u16,               // Penalty fee snapshotted at pool creation
+    pub version: u8,                    // Account layout version
+    pub used_invite_nonces: [u8; MAX_INVITE_NONCES / 8], // Bitmap of redeemed invite ticket nonces
+    pub cycle_contributors: u8,         // Members who contributed in the current cycle
 }
 impl GroupAccount {
+    // Account size with room for `whitelist_len` whitelisted addresses
+    pub fn space(whitelist_len: usize) -> usize {
+        8 + Self::INIT_SPACE - 32 * MAX_WHITELIST_SIZE.saturating_sub(whitelist_len)
+    }
+
     // Add helper methods for phase management
     pub fn initialize_active_status(&mut self) -> Result<()> {
         self.status = PoolStatus::Active { 
@@ -100,6 +148,69 @@ impl GroupAccount {
         Ok(())
     }
 
+    // SOL pool that holds wSOL in an SPL vault instead of lamports in a system PDA
+    pub fn is_wrapped_sol(&self) -> bool {
+        !self.config.is_native_sol
+            && self.token_mint == anchor_spl::token::spl_token::native_mint::id()
+    }
+
+    // Still recruiting but past the join deadline
+    pub fn is_join_deadline_passed(&self, now: i64) -> bool {
+        self.status == PoolStatus::Initializing && now > self.config.join_deadline
+    }
+
+    pub fn is_final_cycle(&self) -> bool {
+        self.current_cycle + 1 == self.total_cycles
+    }
+
+    // payout_order[..current_cycle] holds the members already picked in earlier cycles
+    pub fn is_past_recipient(&self, member: &Pubkey) -> bool {
+        self.payout_order
+            .iter()
+            .take(self.current_cycle as usize)
+            .any(|m| m == member)
+    }
+
+    // Contributions expected from every member in one cycle
+    pub fn cycle_pot(&self) -> Result<u64> {
+        self.config.contribution_amount
+            .checked_mul(self.total_cycles as u64)
+            .ok_or(HuiFiError::Overflow.into())
+    }
+
+    // Smallest and largest bid allowed this cycle
+    pub fn bid_bounds(&self) -> Result<(u64, u64)> {
+        let pot = self.cycle_pot()? as u128;
+        let bound = |bps: u16| (pot * bps as u128 / BASIS_POINTS_DIVISOR as u128) as u64;
+        Ok((bound(self.config.min_discount_bps), bound(self.config.max_discount_bps)))
+    }
+
+    // Record this cycle's recipient and spread their discount over every member's contribution
+    pub fn set_winning_bid(&mut self, winner: Pubkey, bid_amount: u64) -> Result<()> {
+        self.record_recipient(winner)?;
+        let discount_per_member = bid_amount / self.total_cycles as u64;
+        self.current_winner = Some(winner);
+        self.current_bid_amount = Some(bid_amount);
+        self.final_contribution_amount = Some(
+            self.config.contribution_amount
+                .checked_sub(discount_per_member)
+                .ok_or(HuiFiError::Overflow)?,
+        );
+        Ok(())
+    }
+
+    // Move this cycle's recipient into its payout_order slot
+    pub fn record_recipient(&mut self, recipient: Pubkey) -> Result<()> {
+        let cycle = self.current_cycle as usize;
+        let index = self.payout_order
+            .iter()
+            .skip(cycle)
+            .position(|m| *m == recipient)
+            .ok_or(HuiFiError::AlreadyReceivedPayout)?;
+        self.payout_order.swap(cycle, cycle + index);
+        Ok(())
+    }
+
     pub fn get_current_phase(&self) -> Option<CyclePhase> {
         match self.status {
             PoolStatus::Active { phase } => Some(phase),
@@ -107,24 +218,11 @@ impl GroupAccount {
         }
     }
 
-    // Helper to check if all members have contributed for current cycle
+    // Contributions only count once per member per cycle, see MemberAccount::has_contributed_in
     pub fn all_members_contributed(&self) -> bool {
-        self.member_addresses
-            .iter()
-            .filter(|&&member| {
-                // Check if this member has contributed for current cycle
-                let contributions_made = self.get_member_contributions(member);
-                contributions_made > self.current_cycle
-            })
-            .count() as u8 == self.total_cycles
+        self.cycle_contributors as usize >= self.member_addresses.len()
     }
     pub fn is_completed(&self) -> bool {
         self.current_cycle >= self.total_cycles
     }
-    // Helper to check if member has contributed
-    fn get_member_contributions(&self, member: Pubkey) -> u8 {
-        // This would need to be implemented based on how you track contributions
-        // For MVP, you might want to store this in a mapping or similar structure
-        0 // Placeholder
-    }
 }
\ No newline at end of file
@@ -1,25 +1,129 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::HuiFiError;
 
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct ProtocolSettings {
     pub admin: Pubkey,          // Admin address that can update protocol settings
     // pub treasury: Pubkey,           // Treasury account to collect fees
+    #[max_len(MAX_TREASURY_ACCOUNTS)]
     pub treasury_accounts: Vec<TreasuryAccount>,
     pub penalty_bps: u16,           // Penalty fee in basis points (1/100 of 1%)
     pub fee_bps: u16,               // Protocol fee in basis points (1/100 of 1%)
     pub create_pool_fee: u64,      // Create pool fee in SOL
-    pub total_fees_collected: u64,  // Total fees collected by the protocol
+    pub total_fees_collected: u64,  // Total SOL fees collected by the protocol (lamports)
     pub yield_generated: u64,       // Total yield generated 
-    pub reserve_buffer: u64,        // Reserve buffer for emergencies
+    pub reserve_buffer: u64,        // Lamports the SOL treasury keeps back on withdrawal
     pub bump: u8,                   // PDA bump
+    pub pending_admin: Option<Pubkey>, // Proposed admin, must accept before taking over
+    #[max_len(MAX_FEE_EXEMPT_CREATORS)]
+    pub fee_exempt_creators: Vec<Pubkey>, // Creators that do not pay the create pool fee
+    pub paused_flags: u16,          // Bitfield of paused operations (see PAUSE_* constants)
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub role_members: Vec<RoleMember>, // Accounts holding delegated protocol roles
+    pub version: u8,                // Account layout version
+}
+
+// Delegated protocol roles, the admin implicitly holds all of them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    FeeManager,      // Update fees and fee exemptions
+    Pauser,          // Pause and unpause protocol operations
+    Slasher,         // Slash defaulted members' collateral
+    TreasuryManager, // Manage and withdraw from treasuries
+    Operator,        // Force pool cycles forward
+}
+
+impl Role {
+    pub fn flag(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct RoleMember {
+    pub account: Pubkey,
+    pub roles: u8,  // Bitfield of Role::flag values
 }
 
 // Treasury entry for each token mint
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
 pub struct TreasuryAccount {
     pub token_mint: Option<Pubkey>,
     pub treasury: Pubkey,
     pub total_collected: u64,
     pub is_native_sol: bool,
-}
\ No newline at end of file
+    pub open_pools: u32,        // Pools not yet closed or cancelled, the treasury stays while any remain
+    pub create_pool_fee: u64,   // Create pool fee for SPL pools in this mint, in base units
+}
+impl ProtocolSettings {
+    // Look up a treasury entry by mint; `None` is the native SOL treasury
+    pub fn find_treasury(&self, token_mint: Option<Pubkey>) -> Option<&TreasuryAccount> {
+        self.treasury_accounts.iter().find(|t| match token_mint {
+            Some(mint) => !t.is_native_sol && t.token_mint == Some(mint),
+            None => t.is_native_sol,
+        })
+    }
+
+    pub fn find_treasury_mut(&mut self, token_mint: Option<Pubkey>) -> Option<&mut TreasuryAccount> {
+        self.treasury_accounts.iter_mut().find(|t| match token_mint {
+            Some(mint) => !t.is_native_sol && t.token_mint == Some(mint),
+            None => t.is_native_sol,
+        })
+    }
+
+    // Treasury that receives fees and penalties for a pool
+    pub fn treasury_for_pool(&self, token_mint: Pubkey, is_native_sol: bool) -> Option<&TreasuryAccount> {
+        self.find_treasury(if is_native_sol { None } else { Some(token_mint) })
+    }
+
+    // A new pool in this mint, creation fails if the mint has no treasury to pay fees into
+    pub fn register_pool(&mut self, token_mint: Pubkey, is_native_sol: bool) -> Result<()> {
+        let treasury = self
+            .find_treasury_mut(if is_native_sol { None } else { Some(token_mint) })
+            .ok_or(HuiFiError::UnsupportedToken)?;
+        treasury.open_pools = treasury.open_pools
+            .checked_add(1)
+            .ok_or(HuiFiError::Overflow)?;
+        Ok(())
+    }
+
+    // A pool in this mint was closed or cancelled
+    pub fn release_pool(&mut self, token_mint: Pubkey, is_native_sol: bool) {
+        if let Some(treasury) = self.find_treasury_mut(if is_native_sol { None } else { Some(token_mint) }) {
+            treasury.open_pools = treasury.open_pools.saturating_sub(1);
+        }
+    }
+
+    pub fn has_role(&self, account: &Pubkey, role: Role) -> bool {
+        *account == self.admin
+            || self.role_members
+                .iter()
+                .any(|m| m.account == *account && m.roles & role.flag() != 0)
+    }
+
+    pub fn is_paused(&self, flag: u16) -> bool {
+        self.paused_flags & flag != 0
+    }
+
+    pub fn is_fee_exempt(&self, creator: &Pubkey) -> bool {
+        self.fee_exempt_creators.contains(creator)
+    }
+
+    // Record a fee that was moved into the pool's treasury
+    pub fn record_fee(&mut self, token_mint: Pubkey, is_native_sol: bool, amount: u64) -> Result<()> {
+        let treasury = self
+            .find_treasury_mut(if is_native_sol { None } else { Some(token_mint) })
+            .ok_or(HuiFiError::UnsupportedToken)?;
+        treasury.total_collected = treasury.total_collected
+            .checked_add(amount)
+            .ok_or(HuiFiError::Overflow)?;
+        if is_native_sol {
+            self.total_fees_collected = self.total_fees_collected
+                .checked_add(amount)
+                .ok_or(HuiFiError::Overflow)?;
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,22 @@ import * as anchor from '@coral-xyz/anchor';
 import { Program, Idl, AnchorProvider, ProgramAccount } from '@coral-xyz/anchor';
 import { PublicKey, Keypair, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo } from '@solana/spl-token';
+import { SystemProgram, Transaction, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { NATIVE_MINT } from '@solana/spl-token';
+import { createAssociatedTokenAccount } from '@solana/spl-token';
+import { getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { SYSVAR_INSTRUCTIONS_PUBKEY, Ed25519Program } from '@solana/web3.js';
+import { SYSVAR_SLOT_HASHES_PUBKEY } from '@solana/web3.js';
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializePermanentDelegateInstruction,
+} from '@solana/spl-token';
+import { randomBytes } from 'crypto';
+import { createHash } from 'crypto';
 import { assert } from 'chai';
 
 // Import the IDL properly
@@ -260,4 +276,2115 @@ export type UserAccount = {
     total_winnings: anchor.BN,
     experience_points: number,
     bump: number,
-}
\ No newline at end of file
+}
+
+// ==================== PROTOCOL BEHAVIOUR ====================
+
+describe('HuiFi protocol', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+
+  const program = anchor.workspace.ContractsHui as Program<Idl>;
+  // The IDL is not typed here, so instructions and accounts are looked up by name
+  const methods: any = program.methods;
+  const accounts = program.account as unknown as Record<string, {
+    fetch(address: PublicKey): Promise<any>;
+    fetchNullable(address: PublicKey): Promise<any>;
+  }>;
+
+  // The provider wallet is the protocol admin
+  const admin = (provider.wallet as anchor.Wallet).payer;
+
+  const PROTOCOL_FEE_BPS = 100;
+  const PENALTY_BPS = 500; // initialize_protocol's default
+  const CREATE_POOL_FEE = new anchor.BN(10_000_000); // 0.01 SOL
+
+  const seed = (value: string) => Buffer.from(value);
+  const pda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const protocolSettings = pda(seed('huifi-protocol'));
+  const treasurySol = pda(seed('huifi-treasury'), seed('sol'));
+  const treasuryFor = (mint: PublicKey) => pda(seed('huifi-treasury'), mint.toBuffer());
+  const protocolLimits = pda(seed('huifi-limits'));
+  const mintConfigFor = (mint: PublicKey) => pda(seed('huifi-mint-config'), mint.toBuffer());
+
+  async function fundedKeypair(sol = 20): Promise<Keypair> {
+    const keypair = Keypair.generate();
+    const signature = await connection.requestAirdrop(keypair.publicKey, sol * LAMPORTS_PER_SOL);
+    const latest = await connection.getLatestBlockhash();
+    await connection.confirmTransaction({ signature, ...latest }, 'confirmed');
+    return keypair;
+  }
+
+  async function transferSol(to: PublicKey, lamports: number) {
+    await provider.sendAndConfirm(
+      new Transaction().add(SystemProgram.transfer({ fromPubkey: admin.publicKey, toPubkey: to, lamports }))
+    );
+  }
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  // Validator clock, which is what deadlines are checked against
+  async function chainTime(): Promise<number> {
+    return (await connection.getBlockTime(await connection.getSlot())) ?? Math.floor(Date.now() / 1000);
+  }
+
+  // Fails unless `action` is rejected with the given program error
+  async function expectError(action: Promise<unknown>, code: string) {
+    let error: any;
+    try {
+      await action;
+    } catch (e) {
+      error = e;
+    }
+    assert.exists(error, `expected ${code}`);
+    const actual = error?.error?.errorCode?.code ?? String(error);
+    assert.include(actual, code);
+  }
+
+  // Shared protocol state, created once for every block below
+  // Limits loose enough to run whole pools in a test: two members and short cycles
+  const TEST_LIMITS = {
+    minParticipants: 2,
+    maxParticipants: 10,
+    minCycleDuration: new anchor.BN(1),
+    maxCycleDuration: new anchor.BN(28 * 24 * 60 * 60),
+    minPayoutDelay: new anchor.BN(0),
+    maxPayoutDelay: new anchor.BN(7 * 24 * 60 * 60),
+    minCollateralRequirementBps: 13000,
+  };
+
+  let setup: Promise<void> | undefined;
+  function protocolReady(): Promise<void> {
+    setup = setup ?? (async () => {
+      if (!(await connection.getAccountInfo(protocolSettings))) {
+        await methods
+          .initializeProtocol(PROTOCOL_FEE_BPS, CREATE_POOL_FEE)
+          .accountsPartial({
+            admin: admin.publicKey,
+            protocolSettings,
+            treasurySol,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .rpc();
+      }
+      // Fees are paid into the SOL treasury PDA, which has to be rent exempt first
+      if ((await connection.getBalance(treasurySol)) === 0) {
+        await transferSol(treasurySol, LAMPORTS_PER_SOL);
+      }
+      if (!(await connection.getAccountInfo(protocolLimits))) {
+        await methods
+          .initializeProtocolLimits()
+          .accountsPartial({ admin: admin.publicKey, protocolSettings, protocolLimits, systemProgram: SystemProgram.programId })
+          .rpc();
+      }
+      await methods
+        .updateProtocolLimits(TEST_LIMITS)
+        .accountsPartial({ admin: admin.publicKey, protocolSettings, protocolLimits })
+        .rpc();
+      // SOL pools are allowlisted under the native mint, 1 to 100 SOL per contribution
+      if (!(await connection.getAccountInfo(mintConfigFor(NATIVE_MINT)))) {
+        await methods
+          .addSupportedMint(new anchor.BN(1), new anchor.BN(100))
+          .accountsPartial({
+            admin: admin.publicKey,
+            protocolSettings,
+            tokenMint: NATIVE_MINT,
+            mintConfig: mintConfigFor(NATIVE_MINT),
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+      }
+    })();
+    return setup;
+  }
+
+  // A fresh mint, allowlisted for 1 to 1000 whole tokens per contribution
+  async function supportedMint(decimals = 6, tokenProgram = TOKEN_PROGRAM_ID): Promise<PublicKey> {
+    const mint = await createMint(connection, admin, admin.publicKey, null, decimals, Keypair.generate(), undefined, tokenProgram);
+    await allowMint(mint);
+    return mint;
+  }
+
+  const allowMint = (mint: PublicKey) =>
+    methods
+      .addSupportedMint(new anchor.BN(1), new anchor.BN(1000))
+      .accountsPartial({
+        admin: admin.publicKey,
+        protocolSettings,
+        tokenMint: mint,
+        mintConfig: mintConfigFor(mint),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  async function addTreasury(mint: PublicKey, tokenProgram = TOKEN_PROGRAM_ID) {
+    await methods
+      .addTreasury()
+      .accountsPartial({
+        authority: admin.publicKey,
+        protocolSettings,
+        tokenMint: mint,
+        treasury: treasuryFor(mint),
+        tokenProgram,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  async function fundedTokenAccount(mint: PublicKey, owner: PublicKey, amount: number, tokenProgram = TOKEN_PROGRAM_ID) {
+    const account = await createAssociatedTokenAccount(connection, admin, mint, owner, undefined, tokenProgram);
+    if (amount > 0) {
+      await mintTo(connection, admin, mint, account, admin, amount, [], undefined, tokenProgram);
+    }
+    return account;
+  }
+
+  async function tokenBalance(account: PublicKey): Promise<number> {
+    return Number((await connection.getTokenAccountBalance(account)).value.amount);
+  }
+
+  type Pool = {
+    uuid: number[];
+    creator: Keypair;
+    mint: PublicKey;
+    tokenProgram: PublicKey;
+    isNativeSol: boolean;
+    group: PublicKey;
+    bidState: PublicKey;
+    vaultSol: PublicKey;
+    collateralVaultSol: PublicKey;
+    vaultSpl: PublicKey;
+    collateralVaultSpl: PublicKey;
+  };
+
+  const UUID_CHARS = 'ABCDEFGHJKLMNPQRSTUVWXYZ23456789';
+
+  function newPool(creator: Keypair, mint: PublicKey, tokenProgram: PublicKey, isNativeSol: boolean): Pool {
+    const uuid = Array.from(randomBytes(6)).map((byte) => UUID_CHARS.charCodeAt(byte % UUID_CHARS.length));
+    const group = pda(seed('huifi-pool'), Buffer.from(uuid));
+    return {
+      uuid,
+      creator,
+      mint,
+      tokenProgram,
+      isNativeSol,
+      group,
+      bidState: pda(seed('huifi-bid-state'), group.toBuffer()),
+      vaultSol: pda(seed('huifi-vault-sol'), group.toBuffer()),
+      collateralVaultSol: pda(seed('huifi-collateral-vault-sol'), group.toBuffer()),
+      vaultSpl: pda(seed('huifi-vault-spl'), group.toBuffer()),
+      collateralVaultSpl: pda(seed('huifi-collateral-vault-spl'), group.toBuffer()),
+    };
+  }
+
+  const memberFor = (pool: Pool, user: PublicKey) =>
+    pda(seed('huifi-member'), pool.group.toBuffer(), user.toBuffer());
+
+  const fetchGroup = (pool: Pool) => accounts.groupAccount.fetch(pool.group);
+
+  // Two member fixed order pool of 1 SOL contributions with one minute cycles, open for an hour
+  function poolConfig(overrides: Record<string, unknown> = {}) {
+    return {
+      maxParticipants: 2,
+      contributionAmount: new anchor.BN(LAMPORTS_PER_SOL),
+      cycleDurationSeconds: new anchor.BN(60),
+      payoutDelaySeconds: new anchor.BN(0),
+      earlyWithdrawalFeeBps: 200,
+      collateralRequirementBps: 20000,
+      yieldStrategy: { none: {} },
+      isPrivate: false,
+      isNativeSol: true,
+      feedId: new Array(32).fill(0),
+      joinDeadline: new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+      minParticipants: 2,
+      payoutMode: { fixedOrder: {} },
+      minDiscountBps: 0,
+      maxDiscountBps: 5000,
+      tieBreakRule: { earliestBid: {} },
+      ...overrides,
+    };
+  }
+
+  async function createSolPool(
+    creator: Keypair,
+    overrides: Record<string, unknown> = {},
+    whitelist: PublicKey[] | null = null
+  ): Promise<Pool> {
+    const pool = newPool(creator, NATIVE_MINT, TOKEN_PROGRAM_ID, true);
+    await methods
+      .createSolPool(poolConfig(overrides), pool.uuid, whitelist)
+      .accountsPartial({
+        creator: creator.publicKey,
+        groupAccount: pool.group,
+        vaultSol: pool.vaultSol,
+        collateralVault: pool.collateralVaultSol,
+        currentBidState: pool.bidState,
+        memberAccount: memberFor(pool, creator.publicKey),
+        protocolSettings,
+        treasurySol,
+        protocolLimits,
+        mintConfig: mintConfigFor(NATIVE_MINT),
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([creator])
+      .rpc();
+    return pool;
+  }
+
+  async function createSplPool(
+    creator: Keypair,
+    mint: PublicKey,
+    options: {
+      tokenProgram?: PublicKey;
+      overrides?: Record<string, unknown>;
+      creatorTokenAccount?: PublicKey | null;
+      protocolTreasury?: PublicKey | null;
+      whitelist?: PublicKey[] | null;
+    } = {}
+  ): Promise<Pool> {
+    const tokenProgram = options.tokenProgram ?? TOKEN_PROGRAM_ID;
+    const pool = newPool(creator, mint, tokenProgram, false);
+    const config = poolConfig({ isNativeSol: false, contributionAmount: new anchor.BN(10_000_000), ...options.overrides });
+    await methods
+      .createSplPool(config, pool.uuid, options.whitelist ?? null)
+      .accountsPartial({
+        creator: creator.publicKey,
+        groupAccount: pool.group,
+        tokenMint: mint,
+        vaultSpl: pool.vaultSpl,
+        collateralVaultSpl: pool.collateralVaultSpl,
+        currentBidState: pool.bidState,
+        memberAccount: memberFor(pool, creator.publicKey),
+        protocolSettings,
+        creatorTokenAccount: options.creatorTokenAccount ?? null,
+        protocolTreasury: options.protocolTreasury ?? null,
+        protocolLimits,
+        mintConfig: mintConfigFor(mint),
+        tokenProgram,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([creator])
+      .rpc();
+    return pool;
+  }
+
+  type InviteTicket = { poolUuid: number[]; invitee: PublicKey | null; expiresAt: anchor.BN; nonce: number };
+
+  // What the creator signs off-chain, the domain tag followed by the borsh encoded ticket
+  function inviteMessage(ticket: InviteTicket): Buffer {
+    const expiresAt = Buffer.alloc(8);
+    expiresAt.writeBigInt64LE(BigInt(ticket.expiresAt.toString()));
+    const nonce = Buffer.alloc(2);
+    nonce.writeUInt16LE(ticket.nonce);
+    return Buffer.concat([
+      seed('huifi-invite'),
+      Buffer.from(ticket.poolUuid),
+      ticket.invitee ? Buffer.concat([Buffer.from([1]), ticket.invitee.toBuffer()]) : Buffer.from([0]),
+      expiresAt,
+      nonce,
+    ]);
+  }
+
+  async function joinSolPool(pool: Pool, user: Keypair, invite?: { ticket: InviteTicket; signer: Keypair }) {
+    const preInstructions = invite
+      ? [Ed25519Program.createInstructionWithPrivateKey({ privateKey: invite.signer.secretKey, message: inviteMessage(invite.ticket) })]
+      : [];
+    await methods
+      .joinSolPool(pool.uuid, invite?.ticket ?? null)
+      .accountsPartial({
+        user: user.publicKey,
+        groupAccount: pool.group,
+        memberAccount: memberFor(pool, user.publicKey),
+        protocolSettings,
+        instructionsSysvar: invite ? SYSVAR_INSTRUCTIONS_PUBKEY : null,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions(preInstructions)
+      .signers([user])
+      .rpc();
+  }
+
+  // Refunds every member and closes the pool, members are passed in member_addresses order
+  async function cancelPool(pool: Pool) {
+    const group = await fetchGroup(pool);
+    const remainingAccounts = group.memberAddresses.flatMap((member: PublicKey) => {
+      const entries = [
+        { pubkey: memberFor(pool, member), isSigner: false, isWritable: true },
+        { pubkey: member, isSigner: false, isWritable: true },
+      ];
+      if (!pool.isNativeSol) {
+        const tokenAccount = getAssociatedTokenAddressSync(pool.mint, member, false, pool.tokenProgram);
+        entries.push({ pubkey: tokenAccount, isSigner: false, isWritable: true });
+      }
+      return entries;
+    });
+    await methods
+      .cancelPool(pool.uuid)
+      .accountsPartial({
+        creator: pool.creator.publicKey,
+        groupAccount: pool.group,
+        bidState: pool.bidState,
+        vaultSol: pool.isNativeSol ? pool.vaultSol : null,
+        collateralVaultSol: pool.isNativeSol ? pool.collateralVaultSol : null,
+        vaultSpl: pool.isNativeSol ? null : pool.vaultSpl,
+        collateralVaultSpl: pool.isNativeSol ? null : pool.collateralVaultSpl,
+        tokenMint: pool.isNativeSol ? null : pool.mint,
+        protocolSettings,
+        tokenProgram: pool.isNativeSol ? null : pool.tokenProgram,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([pool.creator])
+      .rpc();
+  }
+
+  async function closePool(pool: Pool) {
+    const group = await fetchGroup(pool);
+    const remainingAccounts = group.memberAddresses.flatMap((member: PublicKey) => [
+      { pubkey: memberFor(pool, member), isSigner: false, isWritable: true },
+      { pubkey: member, isSigner: false, isWritable: true },
+    ]);
+    await methods
+      .closePool(pool.uuid)
+      .accountsPartial({
+        signer: pool.creator.publicKey,
+        groupAccount: pool.group,
+        bidState: pool.bidState,
+        protocolSettings,
+        vaultSol: pool.isNativeSol ? pool.vaultSol : null,
+        collateralVaultSol: pool.isNativeSol ? pool.collateralVaultSol : null,
+        treasurySol: pool.isNativeSol ? treasurySol : null,
+        vaultSpl: pool.isNativeSol ? null : pool.vaultSpl,
+        collateralVaultSpl: pool.isNativeSol ? null : pool.collateralVaultSpl,
+        protocolTreasury: pool.isNativeSol ? null : treasuryFor(pool.mint),
+        tokenMint: pool.isNativeSol ? null : pool.mint,
+        tokenProgram: pool.isNativeSol ? null : pool.tokenProgram,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([pool.creator])
+      .rpc();
+  }
+
+  const statusOf = (group: any) => Object.keys(group.status)[0];
+  const phaseOf = (group: any) => Object.keys(group.status.active?.phase ?? {})[0];
+
+  const advanceCycle = (pool: Pool, withSlotHashes = false): Promise<string> =>
+    methods
+      .advanceCycle()
+      .accountsPartial({
+        authority: pool.creator.publicKey,
+        groupAccount: pool.group,
+        bidState: pool.bidState,
+        slotHashes: withSlotHashes ? SYSVAR_SLOT_HASHES_PUBKEY : null,
+      })
+      .signers([pool.creator])
+      .rpc();
+
+  async function contributeSol(pool: Pool, user: Keypair) {
+    const group = await fetchGroup(pool);
+    await methods
+      .contributeSol(pool.uuid, group.finalContributionAmount ?? group.config.contributionAmount)
+      .accountsPartial({
+        contributor: user.publicKey,
+        groupAccount: pool.group,
+        memberAccount: memberFor(pool, user.publicKey),
+        vaultSol: pool.vaultSol,
+        protocolSettings,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+  }
+
+  // Everyone contributes to the awarded cycle, which then becomes claimable by its winner
+  async function fundSolCycle(pool: Pool, members: Keypair[]): Promise<Keypair> {
+    for (const member of members) {
+      await contributeSol(pool, member);
+    }
+    await advanceCycle(pool);
+    const group = await fetchGroup(pool);
+    assert.equal(phaseOf(group), 'readyForPayout');
+    return members.find((member) => member.publicKey.equals(group.currentWinner));
+  }
+
+  async function depositSolCollateral(pool: Pool, user: Keypair) {
+    const group = await fetchGroup(pool);
+    const amount = group.totalContributions.muln(13).divn(10);
+    await methods
+      .depositSolCollateral(pool.uuid, amount)
+      .accountsPartial({
+        user: user.publicKey,
+        groupAccount: pool.group,
+        memberAccount: memberFor(pool, user.publicKey),
+        collateralVaultSol: pool.collateralVaultSol,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+  }
+
+  const processPayout = (pool: Pool, user: Keypair) =>
+    methods
+      .processPayout(pool.uuid)
+      .accountsPartial({
+        user: user.publicKey,
+        groupAccount: pool.group,
+        recipientAccount: memberFor(pool, user.publicKey),
+        vaultSol: pool.vaultSol,
+        protocolSettings,
+        protocolTreasury: treasurySol,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+  const tokenAccountFor = (pool: Pool, user: PublicKey) =>
+    getAssociatedTokenAddressSync(pool.mint, user, false, pool.tokenProgram);
+
+  async function joinSplPool(pool: Pool, user: Keypair, userTokenAccount: PublicKey | null = tokenAccountFor(pool, user.publicKey)) {
+    await methods
+      .joinSplPool(pool.uuid, null)
+      .accountsPartial({
+        user: user.publicKey,
+        groupAccount: pool.group,
+        memberAccount: memberFor(pool, user.publicKey),
+        userTokenAccount,
+        protocolSettings,
+        instructionsSysvar: null,
+        tokenProgram: pool.tokenProgram,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([user])
+      .rpc();
+  }
+
+  async function contributeSpl(
+    pool: Pool,
+    user: Keypair,
+    contributorTokenAccount: PublicKey | null = tokenAccountFor(pool, user.publicKey)
+  ) {
+    const group = await fetchGroup(pool);
+    await methods
+      .contributeSpl(pool.uuid, group.finalContributionAmount ?? group.config.contributionAmount)
+      .accountsPartial({
+        contributor: user.publicKey,
+        groupAccount: pool.group,
+        memberAccount: memberFor(pool, user.publicKey),
+        contributorTokenAccount,
+        vaultSpl: pool.vaultSpl,
+        tokenMint: pool.mint,
+        protocolSettings,
+        tokenProgram: pool.tokenProgram,
+      })
+      .signers([user])
+      .rpc();
+  }
+
+  // Sealed bid, sha256 of the amount, the salt and the bidder
+  function bidCommitment(amount: anchor.BN, salt: Buffer, bidder: PublicKey): number[] {
+    const hash = createHash('sha256')
+      .update(amount.toArrayLike(Buffer, 'le', 8))
+      .update(salt)
+      .update(bidder.toBuffer())
+      .digest();
+    return Array.from(hash);
+  }
+
+  // Commits `amount` and returns the salt needed to reveal it
+  async function commitBid(pool: Pool, bidder: Keypair, amount: anchor.BN): Promise<Buffer> {
+    const salt = randomBytes(32);
+    await methods
+      .commitBid(bidCommitment(amount, salt, bidder.publicKey))
+      .accountsPartial({
+        bidder: bidder.publicKey,
+        bidState: pool.bidState,
+        groupAccount: pool.group,
+        memberAccount: memberFor(pool, bidder.publicKey),
+        protocolSettings,
+      })
+      .signers([bidder])
+      .rpc();
+    return salt;
+  }
+
+  const revealBid = (pool: Pool, bidder: Keypair, amount: anchor.BN, salt: Buffer) =>
+    methods
+      .revealBid(amount, Array.from(salt))
+      .accountsPartial({
+        bidder: bidder.publicKey,
+        bidState: pool.bidState,
+        groupAccount: pool.group,
+        protocolSettings,
+      })
+      .signers([bidder])
+      .rpc();
+
+  const treasuryEntry = async (mint: PublicKey | null) => {
+    const settings = await accounts.protocolSettings.fetch(protocolSettings);
+    return settings.treasuryAccounts.find((entry: any) =>
+      mint === null ? entry.isNativeSol : !entry.isNativeSol && entry.tokenMint?.equals(mint)
+    );
+  };
+
+  describe('governance', () => {
+    const stranger = Keypair.generate();
+
+    before(protocolReady);
+
+    it('updates fees within their maximums', async () => {
+      await methods.updateProtocolFee(250).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc();
+      await methods.updatePenaltyFee(300).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc();
+
+      let settings = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.equal(settings.feeBps, 250);
+      assert.equal(settings.penaltyBps, 300);
+
+      await expectError(
+        methods.updateProtocolFee(1001).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc(),
+        'FeeTooHigh'
+      );
+      await expectError(
+        methods.updatePenaltyFee(1001).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc(),
+        'FeeTooHigh'
+      );
+      await expectError(
+        methods
+          .updateCreatePoolFee(new anchor.BN(LAMPORTS_PER_SOL + 1))
+          .accountsPartial({ authority: admin.publicKey, protocolSettings })
+          .rpc(),
+        'FeeTooHigh'
+      );
+
+      // Restore the defaults the other blocks rely on
+      await methods.updateProtocolFee(PROTOCOL_FEE_BPS).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc();
+      await methods.updatePenaltyFee(PENALTY_BPS).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc();
+      settings = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.equal(settings.feeBps, PROTOCOL_FEE_BPS);
+    });
+
+    it('rejects fee updates from anyone but the admin', async () => {
+      await expectError(
+        methods
+          .updateProtocolFee(300)
+          .accountsPartial({ authority: stranger.publicKey, protocolSettings })
+          .signers([stranger])
+          .rpc(),
+        'Unauthorized'
+      );
+    });
+
+    it('hands the admin role over in two steps', async () => {
+      const newAdmin = await fundedKeypair(2);
+
+      await methods.proposeAdmin(newAdmin.publicKey).accountsPartial({ admin: admin.publicKey, protocolSettings }).rpc();
+      let settings = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.equal(settings.admin.toBase58(), admin.publicKey.toBase58());
+      assert.equal(settings.pendingAdmin.toBase58(), newAdmin.publicKey.toBase58());
+
+      // Only the proposed admin can accept
+      await expectError(
+        methods.acceptAdmin().accountsPartial({ newAdmin: stranger.publicKey, protocolSettings }).signers([stranger]).rpc(),
+        'Unauthorized'
+      );
+      await methods.acceptAdmin().accountsPartial({ newAdmin: newAdmin.publicKey, protocolSettings }).signers([newAdmin]).rpc();
+      settings = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.equal(settings.admin.toBase58(), newAdmin.publicKey.toBase58());
+      assert.isNull(settings.pendingAdmin);
+
+      // The old admin lost its powers, hand the role back
+      await expectError(
+        methods.updateProtocolFee(300).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc(),
+        'Unauthorized'
+      );
+      await methods
+        .proposeAdmin(admin.publicKey)
+        .accountsPartial({ admin: newAdmin.publicKey, protocolSettings })
+        .signers([newAdmin])
+        .rpc();
+      await methods.acceptAdmin().accountsPartial({ newAdmin: admin.publicKey, protocolSettings }).rpc();
+      settings = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.equal(settings.admin.toBase58(), admin.publicKey.toBase58());
+    });
+
+    it('cancels a pending admin transfer', async () => {
+      await methods.proposeAdmin(stranger.publicKey).accountsPartial({ admin: admin.publicKey, protocolSettings }).rpc();
+      await methods.cancelAdminTransfer().accountsPartial({ admin: admin.publicKey, protocolSettings }).rpc();
+
+      const settings = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.isNull(settings.pendingAdmin);
+      await expectError(
+        methods.acceptAdmin().accountsPartial({ newAdmin: stranger.publicKey, protocolSettings }).signers([stranger]).rpc(),
+        'NoPendingAdmin'
+      );
+    });
+  });
+
+  describe('treasury registry', () => {
+    let mint: PublicKey;
+
+    before(async () => {
+      await protocolReady();
+      mint = await supportedMint();
+    });
+
+    it('only lets the treasury manager register a treasury', async () => {
+      const stranger = await fundedKeypair(2);
+      await expectError(
+        methods
+          .addTreasury()
+          .accountsPartial({
+            authority: stranger.publicKey,
+            protocolSettings,
+            tokenMint: mint,
+            treasury: treasuryFor(mint),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .signers([stranger])
+          .rpc(),
+        'Unauthorized'
+      );
+    });
+
+    it('refuses pools in a mint without a treasury', async () => {
+      const creator = await fundedKeypair();
+      await expectError(createSplPool(creator, mint), 'UnsupportedToken');
+    });
+
+    it('registers a treasury per mint', async () => {
+      await addTreasury(mint);
+
+      const entry = await treasuryEntry(mint);
+      assert.equal(entry.treasury.toBase58(), treasuryFor(mint).toBase58());
+      assert.equal(entry.totalCollected.toNumber(), 0);
+      assert.equal(entry.openPools, 0);
+    });
+
+    it('keeps a treasury registered while pools in its mint are open', async () => {
+      const creator = await fundedKeypair();
+      const pool = await createSplPool(creator, mint);
+      assert.equal((await treasuryEntry(mint)).openPools, 1);
+
+      const removeTreasury = () =>
+        methods
+          .removeTreasury()
+          .accountsPartial({
+            authority: admin.publicKey,
+            protocolSettings,
+            tokenMint: mint,
+            treasury: treasuryFor(mint),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+      await expectError(removeTreasury(), 'TreasuryInUse');
+
+      await cancelPool(pool);
+      assert.equal((await treasuryEntry(mint)).openPools, 0);
+
+      await removeTreasury();
+      assert.isUndefined(await treasuryEntry(mint));
+      assert.isNull(await connection.getAccountInfo(treasuryFor(mint)));
+    });
+
+    it('counts SOL pools against the SOL treasury', async () => {
+      const openPoolsBefore = (await treasuryEntry(null)).openPools;
+      const pool = await createSolPool(await fundedKeypair());
+      assert.equal((await treasuryEntry(null)).openPools, openPoolsBefore + 1);
+
+      await cancelPool(pool);
+      assert.equal((await treasuryEntry(null)).openPools, openPoolsBefore);
+    });
+  });
+
+  describe('treasury withdrawals', () => {
+    const setReserveBuffer = (lamports: number) =>
+      methods
+        .updateReserveBuffer(new anchor.BN(lamports))
+        .accountsPartial({ authority: admin.publicKey, protocolSettings })
+        .rpc();
+
+    const withdrawSol = (amount: number, destination: PublicKey, authority: Keypair = admin) =>
+      methods
+        .withdrawTreasury(new anchor.BN(amount))
+        .accountsPartial({
+          authority: authority.publicKey,
+          protocolSettings,
+          treasurySol,
+          destinationSol: destination,
+          treasurySpl: null,
+          destinationSpl: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(authority === admin ? [] : [authority])
+        .rpc();
+
+    before(protocolReady);
+
+    after(async () => {
+      await setReserveBuffer(0);
+    });
+
+    it('only lets the treasury manager withdraw', async () => {
+      const stranger = await fundedKeypair(2);
+      await expectError(withdrawSol(1, stranger.publicKey, stranger), 'Unauthorized');
+    });
+
+    it('keeps the SOL treasury rent exempt and leaves the reserve buffer', async () => {
+      const reserve = LAMPORTS_PER_SOL / 10;
+      await setReserveBuffer(reserve);
+      await transferSol(treasurySol, LAMPORTS_PER_SOL);
+
+      const rent = await connection.getMinimumBalanceForRentExemption(0);
+      const available = (await connection.getBalance(treasurySol)) - rent - reserve;
+      const destination = Keypair.generate().publicKey;
+
+      await expectError(withdrawSol(available + 1, destination), 'InsufficientTreasuryFunds');
+
+      await withdrawSol(available, destination);
+      assert.equal(await connection.getBalance(destination), available);
+      assert.equal(await connection.getBalance(treasurySol), rent + reserve);
+    });
+
+    it('withdraws from an SPL treasury up to its balance', async () => {
+      const mint = await supportedMint();
+      await addTreasury(mint);
+      await mintTo(connection, admin, mint, treasuryFor(mint), admin, 5_000_000);
+      const destination = await fundedTokenAccount(mint, admin.publicKey, 0);
+
+      const withdrawSpl = (amount: number) =>
+        methods
+          .withdrawTreasury(new anchor.BN(amount))
+          .accountsPartial({
+            authority: admin.publicKey,
+            protocolSettings,
+            treasurySol: null,
+            destinationSol: null,
+            treasurySpl: treasuryFor(mint),
+            destinationSpl: destination,
+            tokenMint: mint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+
+      await expectError(withdrawSpl(5_000_001), 'InsufficientTreasuryFunds');
+
+      await withdrawSpl(5_000_000);
+      assert.equal(await tokenBalance(destination), 5_000_000);
+      assert.equal(await tokenBalance(treasuryFor(mint)), 0);
+    });
+  });
+
+  describe('create pool fee', () => {
+    before(protocolReady);
+
+    it('moves the SOL create pool fee into the SOL treasury', async () => {
+      const creator = await fundedKeypair();
+      const balanceBefore = await connection.getBalance(treasurySol);
+      const collectedBefore = (await treasuryEntry(null)).totalCollected.toNumber();
+      const settingsBefore = await accounts.protocolSettings.fetch(protocolSettings);
+
+      const pool = await createSolPool(creator);
+
+      assert.equal(await connection.getBalance(treasurySol), balanceBefore + CREATE_POOL_FEE.toNumber());
+      assert.equal((await treasuryEntry(null)).totalCollected.toNumber(), collectedBefore + CREATE_POOL_FEE.toNumber());
+      const settings = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.equal(
+        settings.totalFeesCollected.toNumber(),
+        settingsBefore.totalFeesCollected.toNumber() + CREATE_POOL_FEE.toNumber()
+      );
+
+      await cancelPool(pool);
+    });
+
+    it('waives the fee for exempt creators', async () => {
+      const creator = await fundedKeypair();
+      const exemption = (method: string) =>
+        methods[method](creator.publicKey).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc();
+      await exemption('addFeeExemptCreator');
+
+      const balanceBefore = await connection.getBalance(treasurySol);
+      const pool = await createSolPool(creator);
+      assert.equal(await connection.getBalance(treasurySol), balanceBefore);
+
+      await cancelPool(pool);
+      await exemption('removeFeeExemptCreator');
+    });
+
+    const updateMintCreatePoolFee = (mint: PublicKey, fee: number) =>
+      methods
+        .updateMintCreatePoolFee(new anchor.BN(fee))
+        .accountsPartial({ authority: admin.publicKey, protocolSettings, tokenMint: mint })
+        .rpc();
+
+    it("charges SPL pools the fee set for the pool's mint, in that mint", async () => {
+      const mint = await supportedMint();
+      await addTreasury(mint);
+      await updateMintCreatePoolFee(mint, 2_000_000);
+
+      const creator = await fundedKeypair();
+      const creatorTokenAccount = await fundedTokenAccount(mint, creator.publicKey, 20_000_000);
+
+      await expectError(createSplPool(creator, mint, { creatorTokenAccount }), 'MissingTreasury');
+
+      const pool = await createSplPool(creator, mint, { creatorTokenAccount, protocolTreasury: treasuryFor(mint) });
+
+      assert.equal(await tokenBalance(treasuryFor(mint)), 2_000_000);
+      assert.equal(await tokenBalance(creatorTokenAccount), 18_000_000);
+      assert.equal((await treasuryEntry(mint)).totalCollected.toNumber(), 2_000_000);
+
+      await cancelPool(pool);
+    });
+
+    it('caps the per mint fee at 100 whole tokens', async () => {
+      const mint = await supportedMint(6);
+      await addTreasury(mint);
+      await expectError(updateMintCreatePoolFee(mint, 100_000_001), 'FeeTooHigh');
+      await updateMintCreatePoolFee(mint, 100_000_000);
+      assert.equal((await treasuryEntry(mint)).createPoolFee.toNumber(), 100_000_000);
+    });
+  });
+
+  describe('protocol fee snapshot', () => {
+    const setFees = (feeBps: number, penaltyBps: number) =>
+      Promise.all([
+        methods.updateProtocolFee(feeBps).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc(),
+        methods.updatePenaltyFee(penaltyBps).accountsPartial({ authority: admin.publicKey, protocolSettings }).rpc(),
+      ]);
+
+    before(protocolReady);
+
+    after(async () => {
+      await setFees(PROTOCOL_FEE_BPS, PENALTY_BPS);
+    });
+
+    it('keeps the fees a pool was created with', async () => {
+      const pool = await createSolPool(await fundedKeypair());
+
+      // Raising the fees afterwards must not reach the live pool
+      await setFees(500, 700);
+      const group = await fetchGroup(pool);
+      assert.equal(group.protocolFeeBps, PROTOCOL_FEE_BPS);
+      assert.equal(group.penaltyBps, PENALTY_BPS);
+
+      const newerPool = await createSolPool(await fundedKeypair());
+      const newer = await fetchGroup(newerPool);
+      assert.equal(newer.protocolFeeBps, 500);
+      assert.equal(newer.penaltyBps, 700);
+
+      await cancelPool(pool);
+      await cancelPool(newerPool);
+    });
+  });
+
+  describe('emergency pause', () => {
+    const PAUSE_CREATE_POOL = 1 << 0;
+    const PAUSE_JOIN_POOL = 1 << 1;
+
+    const setPaused = (method: 'pause' | 'unpause', flags: number, authority: Keypair = admin) =>
+      methods[method](flags)
+        .accountsPartial({ authority: authority.publicKey, protocolSettings })
+        .signers(authority === admin ? [] : [authority])
+        .rpc();
+
+    before(protocolReady);
+
+    afterEach(async () => {
+      const settings = await accounts.protocolSettings.fetch(protocolSettings);
+      if (settings.pausedFlags !== 0) {
+        await setPaused('unpause', settings.pausedFlags);
+      }
+    });
+
+    it('blocks pool creation until unpaused', async () => {
+      const creator = await fundedKeypair();
+      await setPaused('pause', PAUSE_CREATE_POOL);
+      assert.equal((await accounts.protocolSettings.fetch(protocolSettings)).pausedFlags, PAUSE_CREATE_POOL);
+
+      await expectError(createSolPool(creator), 'ProtocolPaused');
+
+      await setPaused('unpause', PAUSE_CREATE_POOL);
+      await cancelPool(await createSolPool(creator));
+    });
+
+    it('pauses each operation on its own', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      await setPaused('pause', PAUSE_JOIN_POOL);
+
+      // Creating is still allowed while joins are paused
+      const pool = await createSolPool(creator);
+      await expectError(joinSolPool(pool, member), 'ProtocolPaused');
+
+      await setPaused('unpause', PAUSE_JOIN_POOL);
+      await joinSolPool(pool, member);
+      assert.equal((await fetchGroup(pool)).memberAddresses.length, 2);
+    });
+
+    it('rejects unknown or empty flags', async () => {
+      await expectError(setPaused('pause', 0), 'InvalidPauseFlags');
+      await expectError(setPaused('pause', 1 << 6), 'InvalidPauseFlags');
+      await expectError(setPaused('unpause', 1 << 6), 'InvalidPauseFlags');
+    });
+
+    it('only lets pausers pause', async () => {
+      const stranger = await fundedKeypair(2);
+      await expectError(setPaused('pause', PAUSE_CREATE_POOL, stranger), 'Unauthorized');
+    });
+  });
+
+  describe('roles', () => {
+    const updateRole = (method: 'grantRole' | 'revokeRole', account: PublicKey, role: object, signer: Keypair = admin) =>
+      methods[method](account, role)
+        .accountsPartial({ admin: signer.publicKey, protocolSettings })
+        .signers(signer === admin ? [] : [signer])
+        .rpc();
+
+    const pauseWith = (authority: Keypair, method: 'pause' | 'unpause') =>
+      methods[method](1).accountsPartial({ authority: authority.publicKey, protocolSettings }).signers([authority]).rpc();
+
+    before(protocolReady);
+
+    it('lets a granted role act without admin rights, until revoked', async () => {
+      const pauser = await fundedKeypair(2);
+      await expectError(pauseWith(pauser, 'pause'), 'Unauthorized');
+
+      await updateRole('grantRole', pauser.publicKey, { pauser: {} });
+      const settings = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.isTrue(settings.roleMembers.some((member: any) => member.account.equals(pauser.publicKey)));
+
+      await pauseWith(pauser, 'pause');
+      await pauseWith(pauser, 'unpause');
+
+      // A role only covers its own instructions
+      await expectError(
+        methods.updateProtocolFee(300).accountsPartial({ authority: pauser.publicKey, protocolSettings }).signers([pauser]).rpc(),
+        'Unauthorized'
+      );
+
+      await updateRole('revokeRole', pauser.publicKey, { pauser: {} });
+      await expectError(pauseWith(pauser, 'pause'), 'Unauthorized');
+      const remaining = await accounts.protocolSettings.fetch(protocolSettings);
+      assert.isFalse(remaining.roleMembers.some((member: any) => member.account.equals(pauser.publicKey)));
+    });
+
+    it('rejects granting a role twice or revoking one never granted', async () => {
+      const manager = Keypair.generate().publicKey;
+      await updateRole('grantRole', manager, { feeManager: {} });
+      await expectError(updateRole('grantRole', manager, { feeManager: {} }), 'RoleAlreadyGranted');
+      await updateRole('revokeRole', manager, { feeManager: {} });
+      await expectError(updateRole('revokeRole', manager, { feeManager: {} }), 'RoleNotGranted');
+    });
+
+    it('only lets the admin manage roles', async () => {
+      const stranger = await fundedKeypair(2);
+      await expectError(updateRole('grantRole', stranger.publicKey, { pauser: {} }, stranger), 'Unauthorized');
+    });
+  });
+
+  describe('protocol limits', () => {
+    const updateLimits = (limits: typeof TEST_LIMITS, signer: Keypair = admin) =>
+      methods
+        .updateProtocolLimits(limits)
+        .accountsPartial({ admin: signer.publicKey, protocolSettings, protocolLimits })
+        .signers(signer === admin ? [] : [signer])
+        .rpc();
+
+    before(protocolReady);
+
+    after(async () => {
+      await updateLimits(TEST_LIMITS);
+    });
+
+    it('rejects inconsistent limits', async () => {
+      const invalid = [
+        { ...TEST_LIMITS, minParticipants: 1 },
+        { ...TEST_LIMITS, minParticipants: 5, maxParticipants: 4 },
+        { ...TEST_LIMITS, maxParticipants: 21 },
+        { ...TEST_LIMITS, minCycleDuration: new anchor.BN(0) },
+        { ...TEST_LIMITS, minCycleDuration: new anchor.BN(100), maxCycleDuration: new anchor.BN(99) },
+        { ...TEST_LIMITS, minPayoutDelay: new anchor.BN(10), maxPayoutDelay: new anchor.BN(9) },
+      ];
+      for (const limits of invalid) {
+        await expectError(updateLimits(limits), 'InvalidProtocolLimits');
+      }
+    });
+
+    it('only lets the admin change the limits', async () => {
+      const stranger = await fundedKeypair(2);
+      await expectError(updateLimits(TEST_LIMITS, stranger), 'Unauthorized');
+    });
+
+    it('refuses pools outside the configured limits', async () => {
+      const creator = await fundedKeypair();
+      const outside = [
+        { maxParticipants: 11 },
+        { cycleDurationSeconds: new anchor.BN(29 * 24 * 60 * 60) },
+        { payoutDelaySeconds: new anchor.BN(8 * 24 * 60 * 60) },
+        { collateralRequirementBps: 12999 },
+        { minParticipants: 3 },
+      ];
+      for (const overrides of outside) {
+        await expectError(createSolPool(creator, overrides), 'InvalidPoolConfig');
+      }
+    });
+
+    it('applies updated limits to new pools', async () => {
+      const creator = await fundedKeypair();
+      await updateLimits({ ...TEST_LIMITS, minParticipants: 3 });
+
+      await expectError(createSolPool(creator), 'InvalidPoolConfig');
+      const pool = await createSolPool(creator, { maxParticipants: 3, minParticipants: 3 });
+      assert.equal((await fetchGroup(pool)).totalCycles, 3);
+
+      await cancelPool(pool);
+      await updateLimits(TEST_LIMITS);
+      assert.equal((await accounts.protocolLimits.fetch(protocolLimits)).limits.minParticipants, 2);
+    });
+  });
+
+  describe('supported mints', () => {
+    before(protocolReady);
+
+    it('bounds SOL contributions in whole SOL', async () => {
+      const creator = await fundedKeypair();
+      await expectError(
+        createSolPool(creator, { contributionAmount: new anchor.BN(LAMPORTS_PER_SOL - 1) }),
+        'ContributionOutOfRange'
+      );
+      await expectError(
+        createSolPool(creator, { contributionAmount: new anchor.BN(100 * LAMPORTS_PER_SOL + 1) }),
+        'ContributionOutOfRange'
+      );
+    });
+
+    it("scales SPL bounds by the mint's decimals", async () => {
+      const mint = await supportedMint(9);
+      await addTreasury(mint);
+      const config = await accounts.mintConfig.fetch(mintConfigFor(mint));
+      assert.equal(config.decimals, 9);
+      assert.equal(config.minContribution.toNumber(), 1);
+      assert.equal(config.maxContribution.toNumber(), 1000);
+
+      const creator = await fundedKeypair();
+      const amount = (tokens: number) => ({ overrides: { contributionAmount: new anchor.BN(tokens * 1e9) } });
+      await expectError(createSplPool(creator, mint, amount(0.5)), 'ContributionOutOfRange');
+      await expectError(createSplPool(creator, mint, amount(1001)), 'ContributionOutOfRange');
+
+      await cancelPool(await createSplPool(creator, mint, amount(1)));
+    });
+
+    it('applies updated bounds and rejects invalid ranges', async () => {
+      const mint = await supportedMint();
+      await addTreasury(mint);
+      const updateBounds = (min: number, max: number) =>
+        methods
+          .updateSupportedMint(new anchor.BN(min), new anchor.BN(max))
+          .accountsPartial({ admin: admin.publicKey, protocolSettings, mintConfig: mintConfigFor(mint) })
+          .rpc();
+
+      await expectError(updateBounds(0, 10), 'InvalidContributionRange');
+      await expectError(updateBounds(20, 10), 'InvalidContributionRange');
+
+      await updateBounds(50, 100);
+      const creator = await fundedKeypair();
+      // The default 10 token contribution is now below the minimum
+      await expectError(createSplPool(creator, mint), 'ContributionOutOfRange');
+    });
+
+    it('refuses pools in mints that are not on the allowlist', async () => {
+      const mint = await supportedMint();
+      await addTreasury(mint);
+      await methods
+        .removeSupportedMint()
+        .accountsPartial({ admin: admin.publicKey, protocolSettings, mintConfig: mintConfigFor(mint) })
+        .rpc();
+      assert.isNull(await connection.getAccountInfo(mintConfigFor(mint)));
+
+      const creator = await fundedKeypair();
+      await expectError(createSplPool(creator, mint), 'AccountNotInitialized');
+    });
+  });
+
+  describe('account migration', () => {
+    // Mirrors the *_VERSION constants in constants.rs
+    const LAYOUT_VERSIONS = {
+      protocolSettings: 1,
+      protocolLimits: 1,
+      mintConfig: 1,
+      groupAccount: 8,
+      memberAccount: 2,
+      bidState: 3,
+    };
+
+    let pool: Pool;
+    let member: PublicKey;
+
+    before(async () => {
+      await protocolReady();
+      const creator = await fundedKeypair();
+      pool = await createSolPool(creator);
+      member = memberFor(pool, creator.publicKey);
+    });
+
+    after(async () => {
+      await cancelPool(pool);
+    });
+
+    it('creates accounts at their current layout version', async () => {
+      assert.equal((await accounts.protocolSettings.fetch(protocolSettings)).version, LAYOUT_VERSIONS.protocolSettings);
+      assert.equal((await accounts.protocolLimits.fetch(protocolLimits)).version, LAYOUT_VERSIONS.protocolLimits);
+      assert.equal((await accounts.mintConfig.fetch(mintConfigFor(NATIVE_MINT))).version, LAYOUT_VERSIONS.mintConfig);
+      assert.equal((await fetchGroup(pool)).version, LAYOUT_VERSIONS.groupAccount);
+      assert.equal((await accounts.memberAccount.fetch(member)).version, LAYOUT_VERSIONS.memberAccount);
+      assert.equal((await accounts.bidState.fetch(pool.bidState)).version, LAYOUT_VERSIONS.bidState);
+    });
+
+    it('refuses to migrate accounts already at the current version', async () => {
+      const systemProgram = SystemProgram.programId;
+      const migrate = (method: string, account: PublicKey, extra: Record<string, PublicKey> = {}) =>
+        methods[method]().accountsPartial({ payer: admin.publicKey, account, systemProgram, ...extra }).rpc();
+
+      await expectError(migrate('migrateProtocolSettings', protocolSettings), 'AlreadyMigrated');
+      await expectError(migrate('migrateGroupAccount', pool.group), 'AlreadyMigrated');
+      await expectError(migrate('migrateMemberAccount', member, { pool: pool.group }), 'AlreadyMigrated');
+      await expectError(migrate('migrateBidState', pool.bidState), 'AlreadyMigrated');
+    });
+  });
+
+  describe('SPL pools', () => {
+    let mint: PublicKey;
+
+    before(async () => {
+      await protocolReady();
+      mint = await supportedMint();
+      await addTreasury(mint);
+    });
+
+    it('sets up the vaults, bid state and creator seat of a token pool', async () => {
+      const creator = await fundedKeypair();
+      const pool = await createSplPool(creator, mint);
+
+      const group = await fetchGroup(pool);
+      assert.isFalse(group.config.isNativeSol);
+      assert.isTrue(group.vault.equals(pool.vaultSpl));
+      assert.equal(await tokenBalance(pool.vaultSpl), 0);
+      assert.equal(await tokenBalance(pool.collateralVaultSpl), 0);
+      assert.isTrue((await accounts.bidState.fetch(pool.bidState)).pool.equals(pool.group));
+      assert.isTrue((await accounts.memberAccount.fetch(memberFor(pool, creator.publicKey))).owner.equals(creator.publicKey));
+
+      await cancelPool(pool);
+    });
+
+    it('lets token holders join, but takes no contributions before the pool starts', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      await fundedTokenAccount(mint, member.publicKey, 100_000_000);
+      const pool = await createSplPool(creator, mint);
+
+      await joinSplPool(pool, member);
+      assert.lengthOf((await fetchGroup(pool)).memberAddresses, 2);
+      await expectError(contributeSpl(pool, member), 'InvalidPhase');
+      await cancelPool(pool);
+    });
+
+    it('refuses SOL instructions on token pools', async () => {
+      const creator = await fundedKeypair();
+      const pool = await createSplPool(creator, mint);
+      await expectError(joinSolPool(pool, await fundedKeypair()), 'InvalidPoolType');
+      await expectError(contributeSol(pool, creator), 'InvalidPoolType');
+      await cancelPool(pool);
+    });
+  });
+
+  describe('Token-2022 mints', () => {
+    // 6 decimal Token-2022 mint carrying the given extensions, set up by `extensionInstructions`
+    async function token2022Mint(
+      extensions: ExtensionType[],
+      extensionInstructions: (mint: PublicKey) => anchor.web3.TransactionInstruction[]
+    ): Promise<PublicKey> {
+      const mint = Keypair.generate();
+      const space = getMintLen(extensions);
+      const transaction = new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: admin.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space,
+          lamports: await connection.getMinimumBalanceForRentExemption(space),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        ...extensionInstructions(mint.publicKey),
+        createInitializeMintInstruction(mint.publicKey, 6, admin.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      );
+      await provider.sendAndConfirm(transaction, [mint]);
+      return mint.publicKey;
+    }
+
+    before(protocolReady);
+
+    it('credits transfer fee mints with what the treasury actually received', async () => {
+      // 1% fee on every transfer
+      const mint = await token2022Mint([ExtensionType.TransferFeeConfig], (mint) => [
+        createInitializeTransferFeeConfigInstruction(mint, admin.publicKey, admin.publicKey, 100, BigInt(1e12), TOKEN_2022_PROGRAM_ID),
+      ]);
+      await allowMint(mint);
+      await addTreasury(mint, TOKEN_2022_PROGRAM_ID);
+      await methods
+        .updateMintCreatePoolFee(new anchor.BN(2_000_000))
+        .accountsPartial({ authority: admin.publicKey, protocolSettings, tokenMint: mint })
+        .rpc();
+
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const creatorTokenAccount = await fundedTokenAccount(mint, creator.publicKey, 100_000_000, TOKEN_2022_PROGRAM_ID);
+      await fundedTokenAccount(mint, member.publicKey, 100_000_000, TOKEN_2022_PROGRAM_ID);
+
+      const pool = await createSplPool(creator, mint, {
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        creatorTokenAccount,
+        protocolTreasury: treasuryFor(mint),
+      });
+      assert.equal(await tokenBalance(treasuryFor(mint)), 1_980_000);
+      assert.equal((await treasuryEntry(mint)).totalCollected.toNumber(), 1_980_000);
+
+      await joinSplPool(pool, member);
+      assert.lengthOf((await fetchGroup(pool)).memberAddresses, 2);
+      await cancelPool(pool);
+    });
+
+    it('refuses mints with a permanent delegate', async () => {
+      const mint = await token2022Mint([ExtensionType.PermanentDelegate], (mint) => [
+        createInitializePermanentDelegateInstruction(mint, admin.publicKey, TOKEN_2022_PROGRAM_ID),
+      ]);
+      await expectError(allowMint(mint), 'UnsupportedMintExtension');
+    });
+  });
+
+  describe('wrapped SOL pools', () => {
+    before(async () => {
+      await protocolReady();
+      if (!(await treasuryEntry(NATIVE_MINT))) {
+        await addTreasury(NATIVE_MINT);
+      }
+    });
+
+    it('wraps the create pool fee and the entry check from lamports', async () => {
+      await methods
+        .updateMintCreatePoolFee(new anchor.BN(10_000_000))
+        .accountsPartial({ authority: admin.publicKey, protocolSettings, tokenMint: NATIVE_MINT })
+        .rpc();
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const treasuryBefore = await tokenBalance(treasuryFor(NATIVE_MINT));
+
+      // Nobody holds a wSOL account, the fee is wrapped straight into the wSOL treasury
+      const pool = await createSplPool(creator, NATIVE_MINT, {
+        overrides: { contributionAmount: new anchor.BN(LAMPORTS_PER_SOL) },
+        protocolTreasury: treasuryFor(NATIVE_MINT),
+      });
+      assert.equal(await tokenBalance(treasuryFor(NATIVE_MINT)), treasuryBefore + 10_000_000);
+
+      await joinSplPool(pool, member, null);
+      assert.lengthOf((await fetchGroup(pool)).memberAddresses, 2);
+      await cancelPool(pool);
+
+      await methods
+        .updateMintCreatePoolFee(new anchor.BN(0))
+        .accountsPartial({ authority: admin.publicKey, protocolSettings, tokenMint: NATIVE_MINT })
+        .rpc();
+    });
+
+    it('only wraps for wrapped SOL pools', async () => {
+      const mint = await supportedMint();
+      await addTreasury(mint);
+      const pool = await createSplPool(await fundedKeypair(), mint);
+
+      await expectError(joinSplPool(pool, await fundedKeypair(), null), 'MissingTokenAccount');
+      await cancelPool(pool);
+    });
+  });
+
+  describe('leaving a pool', () => {
+    const leaveSolPool = (pool: Pool, user: Keypair) =>
+      methods
+        .leavePool(pool.uuid, false)
+        .accountsPartial({
+          user: user.publicKey,
+          groupAccount: pool.group,
+          memberAccount: memberFor(pool, user.publicKey),
+          vaultSol: pool.vaultSol,
+          collateralVaultSol: pool.collateralVaultSol,
+          vaultSpl: null,
+          collateralVaultSpl: null,
+          userTokenAccount: null,
+          tokenMint: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+    before(protocolReady);
+
+    it('lets a member back out before activation and returns the seat rent', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const pool = await createSolPool(creator, { maxParticipants: 3 });
+
+      const balanceBefore = await connection.getBalance(member.publicKey);
+      await joinSolPool(pool, member);
+      await leaveSolPool(pool, member);
+
+      assert.isNull(await connection.getAccountInfo(memberFor(pool, member.publicKey)));
+      const group = await fetchGroup(pool);
+      assert.equal(statusOf(group), 'initializing');
+      assert.isFalse(group.memberAddresses.some((address: PublicKey) => address.equals(member.publicKey)));
+      // Only the two transaction fees are gone
+      assert.isAbove(await connection.getBalance(member.publicKey), balanceBefore - 20_000);
+
+      await cancelPool(pool);
+    });
+
+    it('cancels the pool when its creator leaves', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const pool = await createSolPool(creator, { maxParticipants: 3 });
+      await joinSolPool(pool, member);
+
+      await leaveSolPool(pool, creator);
+      assert.equal(statusOf(await fetchGroup(pool)), 'cancelled');
+
+      // Everyone else can still walk away from the cancelled pool
+      await leaveSolPool(pool, member);
+      assert.isEmpty((await fetchGroup(pool)).memberAddresses);
+      await cancelPool(pool);
+    });
+
+    it('is closed once the pool is running', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const pool = await createSolPool(creator);
+      await joinSolPool(pool, member);
+
+      await expectError(leaveSolPool(pool, member), 'InvalidPoolStatus');
+    });
+  });
+
+  describe('cancelling a pool', () => {
+    before(protocolReady);
+
+    it('refunds every member and closes the SOL pool accounts', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const pool = await createSolPool(creator, { maxParticipants: 3 });
+      await joinSolPool(pool, member);
+
+      const memberRent = await connection.getBalance(memberFor(pool, member.publicKey));
+      const balanceBefore = await connection.getBalance(member.publicKey);
+      await cancelPool(pool);
+
+      assert.equal(await connection.getBalance(member.publicKey), balanceBefore + memberRent);
+      for (const account of [pool.group, pool.bidState, pool.vaultSol, pool.collateralVaultSol, memberFor(pool, member.publicKey)]) {
+        assert.isNull(await connection.getAccountInfo(account));
+      }
+    });
+
+    it('closes the token vaults of SPL pools', async () => {
+      const mint = await supportedMint();
+      await addTreasury(mint);
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      await fundedTokenAccount(mint, member.publicKey, 10_000_000);
+      const pool = await createSplPool(creator, mint, { overrides: { maxParticipants: 3 } });
+      await joinSplPool(pool, member);
+
+      await cancelPool(pool);
+
+      for (const account of [pool.group, pool.vaultSpl, pool.collateralVaultSpl, memberFor(pool, member.publicKey)]) {
+        assert.isNull(await connection.getAccountInfo(account));
+      }
+      assert.equal((await treasuryEntry(mint)).openPools, 0);
+    });
+
+    it('is reserved to the creator and needs every member', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const pool = await createSolPool(creator, { maxParticipants: 3 });
+      await joinSolPool(pool, member);
+
+      await expectError(cancelPool({ ...pool, creator: member }), 'Unauthorized');
+      await expectError(
+        methods
+          .cancelPool(pool.uuid)
+          .accountsPartial({
+            creator: creator.publicKey,
+            groupAccount: pool.group,
+            bidState: pool.bidState,
+            vaultSol: pool.vaultSol,
+            collateralVaultSol: pool.collateralVaultSol,
+            vaultSpl: null,
+            collateralVaultSpl: null,
+            tokenMint: null,
+            protocolSettings,
+            tokenProgram: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts([
+            { pubkey: memberFor(pool, creator.publicKey), isSigner: false, isWritable: true },
+            { pubkey: creator.publicKey, isSigner: false, isWritable: true },
+          ])
+          .signers([creator])
+          .rpc(),
+        'InvalidRemainingAccounts'
+      );
+
+      await cancelPool(pool);
+    });
+
+    it('is refused once the pool is running', async () => {
+      const creator = await fundedKeypair();
+      const pool = await createSolPool(creator);
+      await joinSolPool(pool, await fundedKeypair());
+
+      await expectError(cancelPool(pool), 'InvalidPoolStatus');
+    });
+  });
+
+  describe('join deadline', () => {
+    const expirePool = (pool: Pool, signer: Keypair) =>
+      methods
+        .expirePool(pool.uuid)
+        .accountsPartial({ signer: signer.publicKey, groupAccount: pool.group })
+        .signers([signer])
+        .rpc();
+
+    before(protocolReady);
+
+    it('rejects deadlines that already passed', async () => {
+      const creator = await fundedKeypair();
+      await expectError(
+        createSolPool(creator, { joinDeadline: new anchor.BN((await chainTime()) - 1) }),
+        'InvalidJoinDeadline'
+      );
+    });
+
+    it('only expires a pool once its deadline passed', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const anyone = await fundedKeypair(2);
+      const pool = await createSolPool(creator, { joinDeadline: new anchor.BN((await chainTime()) + 3) });
+
+      await expectError(expirePool(pool, anyone), 'JoinDeadlineNotReached');
+
+      while ((await chainTime()) <= (await fetchGroup(pool)).config.joinDeadline.toNumber()) {
+        await sleep(1000);
+      }
+      await expectError(joinSolPool(pool, member), 'JoinDeadlinePassed');
+
+      // Anyone may expire it, after which the creator cleans up
+      await expirePool(pool, anyone);
+      assert.equal(statusOf(await fetchGroup(pool)), 'expired');
+      await expectError(expirePool(pool, anyone), 'InvalidPoolStatus');
+      await cancelPool(pool);
+      assert.isNull(await connection.getAccountInfo(pool.group));
+    });
+  });
+
+  describe('starting below capacity', () => {
+    const startPool = (pool: Pool, creator: Keypair = pool.creator) =>
+      methods
+        .startPool(pool.uuid)
+        .accountsPartial({ creator: creator.publicKey, groupAccount: pool.group, bidState: pool.bidState })
+        .signers([creator])
+        .rpc();
+
+    before(protocolReady);
+
+    it('starts once the quorum joined, sized to the members present', async () => {
+      const creator = await fundedKeypair();
+      const members = [await fundedKeypair(), await fundedKeypair()];
+      const pool = await createSolPool(creator, { maxParticipants: 4, minParticipants: 3 });
+
+      await joinSolPool(pool, members[0]);
+      await expectError(startPool(pool), 'QuorumNotReached');
+
+      await joinSolPool(pool, members[1]);
+      await expectError(startPool(pool, members[0]), 'Unauthorized');
+      await startPool(pool);
+
+      const group = await fetchGroup(pool);
+      assert.equal(phaseOf(group), 'bidding');
+      assert.equal(group.totalCycles, 3);
+      assert.equal(group.payoutOrder.length, 3);
+
+      // The remaining seat is gone
+      await expectError(joinSolPool(pool, await fundedKeypair()), 'InvalidPoolStatus');
+      await expectError(startPool(pool), 'InvalidPoolStatus');
+    });
+  });
+
+  describe('closing a completed pool', () => {
+    const withdrawSolCollateral = (pool: Pool, user: Keypair) =>
+      methods
+        .withdrawSolCollateral(pool.uuid)
+        .accountsPartial({
+          user: user.publicKey,
+          groupAccount: pool.group,
+          memberAccount: memberFor(pool, user.publicKey),
+          collateralVaultSol: pool.collateralVaultSol,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+    before(protocolReady);
+
+    it('refuses pools that have not completed', async () => {
+      const creator = await fundedKeypair();
+      const pool = await createSolPool(creator);
+      await expectError(closePool(pool), 'InvalidPoolStatus');
+
+      // A full pool is running, not done
+      await joinSolPool(pool, await fundedKeypair());
+      assert.equal(statusOf(await fetchGroup(pool)), 'active');
+      await expectError(closePool(pool), 'InvalidPoolStatus');
+    });
+
+    it('waits for collateral to be withdrawn, then closes every account', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const members = [creator, member];
+      const pool = await createSolPool(creator);
+      await joinSolPool(pool, member);
+      await advanceCycle(pool);
+      await expectError(closePool(pool), 'InvalidPoolStatus');
+
+      const first = await fundSolCycle(pool, members);
+      await depositSolCollateral(pool, first);
+      await processPayout(pool, first);
+      await advanceCycle(pool);
+      const last = await fundSolCycle(pool, members);
+      await processPayout(pool, last);
+      await advanceCycle(pool);
+      assert.equal(statusOf(await fetchGroup(pool)), 'completed');
+
+      const openPoolsBefore = (await treasuryEntry(null)).openPools;
+      await expectError(closePool(pool), 'CollateralNotWithdrawn');
+
+      await withdrawSolCollateral(pool, first);
+      await expectError(withdrawSolCollateral(pool, first), 'MemberAlreadyWithdrawed');
+
+      const memberRent = await connection.getBalance(memberFor(pool, member.publicKey));
+      const memberBalance = await connection.getBalance(member.publicKey);
+      await closePool(pool);
+
+      assert.equal(await connection.getBalance(member.publicKey), memberBalance + memberRent);
+      for (const account of [pool.group, pool.bidState, memberFor(pool, creator.publicKey), memberFor(pool, member.publicKey)]) {
+        assert.isNull(await connection.getAccountInfo(account));
+      }
+      assert.equal((await treasuryEntry(null)).openPools, openPoolsBefore - 1);
+    });
+
+    it('refuses to move past a payout nobody has claimed', async () => {
+      const creator = await fundedKeypair();
+      const member = await fundedKeypair();
+      const members = [creator, member];
+      const pool = await createSolPool(creator);
+      await joinSolPool(pool, member);
+      await advanceCycle(pool);
+
+      // The pot has to be claimed before the next cycle starts
+      const first = await fundSolCycle(pool, members);
+      const group = await fetchGroup(pool);
+      assert.equal(group.unclaimedPayout.toNumber(), group.totalContributions.toNumber());
+      await expectError(advanceCycle(pool), 'UnclaimedPayouts');
+
+      await depositSolCollateral(pool, first);
+      await processPayout(pool, first);
+      assert.equal((await fetchGroup(pool)).unclaimedPayout.toNumber(), 0);
+      await advanceCycle(pool);
+      assert.equal((await fetchGroup(pool)).currentCycle, 1);
+    });
+  });
+
+  describe('whitelist', () => {
+    const updateWhitelist = (method: 'addToWhitelist' | 'removeFromWhitelist', pool: Pool, wallet: PublicKey, creator: Keypair = pool.creator) =>
+      methods[method](pool.uuid, wallet)
+        .accountsPartial({ creator: creator.publicKey, groupAccount: pool.group, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+
+    before(protocolReady);
+
+    it('lets the creator invite wallets it forgot at creation', async () => {
+      const creator = await fundedKeypair();
+      const [invited, forgotten] = [await fundedKeypair(), await fundedKeypair()];
+      const pool = await createSolPool(creator, { isPrivate: true, maxParticipants: 3 }, [invited.publicKey]);
+
+      await expectError(joinSolPool(pool, forgotten), 'NotWhitelisted');
+      await expectError(updateWhitelist('addToWhitelist', pool, forgotten.publicKey, invited), 'Unauthorized');
+
+      const sizeBefore = (await connection.getAccountInfo(pool.group)).data.length;
+      await updateWhitelist('addToWhitelist', pool, forgotten.publicKey);
+      assert.equal((await connection.getAccountInfo(pool.group)).data.length, sizeBefore + 32);
+      await expectError(updateWhitelist('addToWhitelist', pool, forgotten.publicKey), 'AlreadyWhitelisted');
+
+      await joinSolPool(pool, forgotten);
+      await joinSolPool(pool, invited);
+      assert.equal((await fetchGroup(pool)).memberAddresses.length, 3);
+    });
+
+    it('only removes wallets that have not joined', async () => {
+      const creator = await fundedKeypair();
+      const [joined, pending] = [await fundedKeypair(), await fundedKeypair()];
+      const pool = await createSolPool(creator, { isPrivate: true, maxParticipants: 3 }, [joined.publicKey, pending.publicKey]);
+      await joinSolPool(pool, joined);
+
+      await expectError(updateWhitelist('removeFromWhitelist', pool, joined.publicKey), 'WhitelistedMemberJoined');
+      await expectError(updateWhitelist('removeFromWhitelist', pool, creator.publicKey), 'NotWhitelisted');
+
+      await updateWhitelist('removeFromWhitelist', pool, pending.publicKey);
+      assert.equal((await fetchGroup(pool)).whitelist.length, 1);
+      await expectError(joinSolPool(pool, pending), 'NotWhitelisted');
+
+      await cancelPool(pool);
+    });
+
+    it('will not empty the whitelist of a public pool, which would open it to everyone', async () => {
+      const creator = await fundedKeypair();
+      const invited = await fundedKeypair();
+      const pool = await createSolPool(creator, { maxParticipants: 3 }, [invited.publicKey]);
+
+      await expectError(updateWhitelist('removeFromWhitelist', pool, invited.publicKey), 'WhitelistCannotBeEmptied');
+      await expectError(joinSolPool(pool, await fundedKeypair()), 'NotWhitelisted');
+
+      await cancelPool(pool);
+    });
+  });
+
+  describe('invite tickets', () => {
+    let creator: Keypair;
+    let pool: Pool;
+
+    const ticketFor = async (overrides: Partial<InviteTicket> = {}): Promise<InviteTicket> => ({
+      poolUuid: pool.uuid,
+      invitee: null,
+      expiresAt: new anchor.BN((await chainTime()) + 600),
+      nonce: 0,
+      ...overrides,
+    });
+
+    before(async () => {
+      await protocolReady();
+      creator = await fundedKeypair();
+      // Private and without a whitelist, tickets are the only way in
+      pool = await createSolPool(creator, { isPrivate: true, maxParticipants: 5 });
+    });
+
+    it('admits the holder of a ticket signed by the creator, once', async () => {
+      const [holder, copier] = [await fundedKeypair(), await fundedKeypair()];
+      await expectError(joinSolPool(pool, holder), 'NotWhitelisted');
+
+      const ticket = await ticketFor({ nonce: 1 });
+      await joinSolPool(pool, holder, { ticket, signer: creator });
+      assert.isTrue((await fetchGroup(pool)).memberAddresses.some((address: PublicKey) => address.equals(holder.publicKey)));
+
+      await expectError(joinSolPool(pool, copier, { ticket, signer: creator }), 'InviteTicketUsed');
+    });
+
+    it('rejects tickets not signed by the creator or meant for someone else', async () => {
+      const [user, invitee] = [await fundedKeypair(), await fundedKeypair()];
+
+      await expectError(joinSolPool(pool, user, { ticket: await ticketFor({ nonce: 2 }), signer: user }), 'InvalidInviteTicket');
+
+      const named = await ticketFor({ nonce: 3, invitee: invitee.publicKey });
+      await expectError(joinSolPool(pool, user, { ticket: named, signer: creator }), 'InvalidInviteTicket');
+      await joinSolPool(pool, invitee, { ticket: named, signer: creator });
+
+      const otherPool = await ticketFor({ nonce: 4, poolUuid: newPool(creator, NATIVE_MINT, TOKEN_PROGRAM_ID, true).uuid });
+      await expectError(joinSolPool(pool, user, { ticket: otherPool, signer: creator }), 'InvalidInviteTicket');
+    });
+
+    it('rejects expired tickets and nonces out of range', async () => {
+      const user = await fundedKeypair();
+      const expired = await ticketFor({ nonce: 5, expiresAt: new anchor.BN((await chainTime()) - 10) });
+      await expectError(joinSolPool(pool, user, { ticket: expired, signer: creator }), 'InviteTicketExpired');
+      await expectError(joinSolPool(pool, user, { ticket: await ticketFor({ nonce: 256 }), signer: creator }), 'InvalidInviteTicket');
+    });
+
+    after(async () => {
+      await cancelPool(pool);
+    });
+  });
+
+  describe('sealed bid auctions', () => {
+    const sol = (amount: number) => new anchor.BN(amount * LAMPORTS_PER_SOL);
+
+    // Three member auction over a 3 SOL pot
+    async function auctionPool(): Promise<{ pool: Pool; members: Keypair[] }> {
+      const creator = await fundedKeypair();
+      const members = [creator, await fundedKeypair(), await fundedKeypair()];
+      const pool = await createSolPool(creator, { maxParticipants: 3, payoutMode: { auction: {} } });
+      for (const member of members.slice(1)) {
+        await joinSolPool(pool, member);
+      }
+      return { pool, members };
+    }
+
+    const finalizeBidding = (pool: Pool, authority: Keypair) =>
+      methods
+        .finalizeBidding(pool.uuid)
+        .accountsPartial({ authority: authority.publicKey, bidState: pool.bidState, groupAccount: pool.group, slotHashes: null })
+        .signers([authority])
+        .rpc();
+
+    before(protocolReady);
+
+    it('awards the highest revealed bid and ignores unrevealed commitments', async () => {
+      const { pool, members } = await auctionPool();
+      const [alice, bob, carol] = members;
+
+      await expectError(advanceCycle(pool), 'NoBids');
+      const aliceSalt = await commitBid(pool, alice, sol(0.3));
+      const bobSalt = await commitBid(pool, bob, sol(0.5));
+      // Carol outbids everyone but never reveals
+      await commitBid(pool, carol, sol(1));
+      await expectError(commitBid(pool, bob, sol(0.6)), 'AlreadyBid');
+      await expectError(revealBid(pool, bob, sol(0.5), bobSalt), 'InvalidPhase');
+
+      await advanceCycle(pool);
+      assert.equal(phaseOf(await fetchGroup(pool)), 'revealing');
+      await expectError(commitBid(pool, bob, sol(0.6)), 'InvalidPhase');
+
+      // The commitment binds both the amount and the salt
+      await expectError(revealBid(pool, bob, sol(0.6), bobSalt), 'InvalidBidReveal');
+      await expectError(revealBid(pool, bob, sol(0.5), randomBytes(32)), 'InvalidBidReveal');
+      await revealBid(pool, alice, sol(0.3), aliceSalt);
+      await revealBid(pool, bob, sol(0.5), bobSalt);
+      await expectError(revealBid(pool, bob, sol(0.5), bobSalt), 'BidAlreadyRevealed');
+
+      await expectError(finalizeBidding(pool, bob), 'Unauthorized');
+      await finalizeBidding(pool, alice);
+
+      const group = await fetchGroup(pool);
+      assert.equal(phaseOf(group), 'contributing');
+      assert.equal(group.currentWinner.toBase58(), bob.publicKey.toBase58());
+      assert.equal(group.currentBidAmount.toString(), sol(0.5).toString());
+    });
+
+    it('pays the next member in payout order when nobody reveals', async () => {
+      const { pool, members } = await auctionPool();
+      await commitBid(pool, members[1], sol(0.5));
+      await advanceCycle(pool);
+      await advanceCycle(pool);
+
+      const group = await fetchGroup(pool);
+      assert.equal(phaseOf(group), 'contributing');
+      assert.equal(group.currentWinner.toBase58(), group.payoutOrder[0].toBase58());
+      assert.equal(group.currentBidAmount.toNumber(), 0);
+    });
+  });
+
+  describe('payout modes', () => {
+    async function fullPool(payoutMode: object): Promise<{ pool: Pool; members: Keypair[] }> {
+      const creator = await fundedKeypair();
+      const members = [creator, await fundedKeypair(), await fundedKeypair()];
+      const pool = await createSolPool(creator, { maxParticipants: 3, payoutMode });
+      for (const member of members.slice(1)) {
+        await joinSolPool(pool, member);
+      }
+      return { pool, members };
+    }
+
+    before(protocolReady);
+
+    it('pays fixed order pools in join order without bidding', async () => {
+      const { pool, members } = await fullPool({ fixedOrder: {} });
+      await expectError(commitBid(pool, members[1], new anchor.BN(1)), 'InvalidPayoutMode');
+
+      const payoutOrder = (await fetchGroup(pool)).payoutOrder.map((member: PublicKey) => member.toBase58());
+      assert.deepEqual(payoutOrder, members.map((member) => member.publicKey.toBase58()));
+
+      for (const [cycle, expected] of members.slice(0, 2).entries()) {
+        if (cycle > 0) {
+          await advanceCycle(pool);
+        }
+        // Bidding is skipped straight to the member whose turn it is
+        if (phaseOf(await fetchGroup(pool)) === 'bidding') {
+          await advanceCycle(pool);
+        }
+        const group = await fetchGroup(pool);
+        assert.equal(group.currentWinner.toBase58(), expected.publicKey.toBase58());
+        assert.equal(group.finalContributionAmount.toNumber(), LAMPORTS_PER_SOL);
+        const winner = await fundSolCycle(pool, members);
+        await depositSolCollateral(pool, winner);
+        await processPayout(pool, winner);
+      }
+    });
+
+    it('draws lottery recipients from SlotHashes among members still waiting', async () => {
+      const { pool, members } = await fullPool({ lottery: {} });
+      await expectError(commitBid(pool, members[1], new anchor.BN(1)), 'InvalidPayoutMode');
+      await expectError(advanceCycle(pool), 'MissingRandomnessSource');
+
+      await advanceCycle(pool, true);
+      const group = await fetchGroup(pool);
+      assert.equal(phaseOf(group), 'contributing');
+      const winner = members.find((member) => member.publicKey.equals(group.currentWinner));
+      assert.isDefined(winner);
+      // The drawn member is moved to this cycle's slot of the payout order
+      assert.equal(group.payoutOrder[0].toBase58(), winner.publicKey.toBase58());
+
+      assert.equal((await fundSolCycle(pool, members)).publicKey.toBase58(), winner.publicKey.toBase58());
+      await depositSolCollateral(pool, winner);
+      await processPayout(pool, winner);
+      assert.isTrue((await accounts.memberAccount.fetch(memberFor(pool, winner.publicKey))).hasReceivedPayout);
+    });
+  });
+
+  describe('cycle bookkeeping', () => {
+    const sol = (amount: number) => new anchor.BN(amount * LAMPORTS_PER_SOL);
+
+    // Each bidder bids a tenth of a SOL more than the one before, so the last one wins
+    async function runAuction(pool: Pool, bidders: Keypair[]): Promise<Keypair> {
+      const bids = bidders.map((_, index) => sol((index + 1) / 10));
+      const salts = [];
+      for (const [index, bidder] of bidders.entries()) {
+        salts.push(await commitBid(pool, bidder, bids[index]));
+      }
+      await advanceCycle(pool);
+      for (const [index, bidder] of bidders.entries()) {
+        await revealBid(pool, bidder, bids[index], salts[index]);
+      }
+      await advanceCycle(pool);
+      const group = await fetchGroup(pool);
+      assert.equal(group.currentWinner.toBase58(), bidders[bidders.length - 1].publicKey.toBase58());
+      return bidders[bidders.length - 1];
+    }
+
+    before(protocolReady);
+
+    it('lets members bid and contribute again every cycle, past winners excluded', async () => {
+      const creator = await fundedKeypair();
+      const members = [creator, await fundedKeypair(), await fundedKeypair()];
+      const pool = await createSolPool(creator, { maxParticipants: 3, payoutMode: { auction: {} } });
+      for (const member of members.slice(1)) {
+        await joinSolPool(pool, member);
+      }
+
+      const first = await runAuction(pool, members);
+      // The winning bid is spread over every member's contribution
+      assert.equal((await fetchGroup(pool)).finalContributionAmount.toString(), sol(0.9).toString());
+
+      // Everyone has to pay in before the pot can be claimed
+      await contributeSol(pool, members[0]);
+      await expectError(contributeSol(pool, members[0]), 'HasAlreadyContributed');
+      await expectError(advanceCycle(pool), 'PendingContributions');
+      for (const member of members.slice(1)) {
+        await contributeSol(pool, member);
+      }
+      await advanceCycle(pool);
+      await depositSolCollateral(pool, first);
+      await processPayout(pool, first);
+      await expectError(processPayout(pool, first), 'AlreadyReceivedPayout');
+      await advanceCycle(pool);
+
+      // Second cycle, the first winner sits the auction out
+      await expectError(commitBid(pool, first, sol(0.1)), 'AlreadyReceivedPayout');
+      const waiting = members.filter((member) => member !== first);
+      const second = await runAuction(pool, waiting);
+      const winner = await fundSolCycle(pool, members);
+      assert.equal(winner.publicKey.toBase58(), second.publicKey.toBase58());
+      await depositSolCollateral(pool, second);
+      await processPayout(pool, second);
+      await advanceCycle(pool);
+
+      // The last member is awarded the final pot without an auction
+      const group = await fetchGroup(pool);
+      const last = waiting.find((member) => member !== second);
+      assert.equal(group.currentCycle, 2);
+      assert.equal(phaseOf(group), 'contributing');
+      assert.equal(group.currentWinner.toBase58(), last.publicKey.toBase58());
+      await fundSolCycle(pool, members);
+      await processPayout(pool, last);
+      await advanceCycle(pool);
+      assert.equal(statusOf(await fetchGroup(pool)), 'completed');
+    });
+
+    it('only lets operators force a cycle forward', async () => {
+      const creator = await fundedKeypair();
+      const pool = await createSolPool(creator);
+      await joinSolPool(pool, await fundedKeypair());
+
+      await expectError(
+        methods
+          .forceAdvanceCycle()
+          .accountsPartial({
+            authority: creator.publicKey,
+            protocolSettings,
+            groupAccount: pool.group,
+            bidState: pool.bidState,
+            slotHashes: null,
+          })
+          .signers([creator])
+          .rpc(),
+        'Unauthorized'
+      );
+    });
+  });
+
+  describe('bid bounds', () => {
+    const sol = (amount: number) => new anchor.BN(Math.round(amount * LAMPORTS_PER_SOL));
+
+    // Three member auction over a 3 SOL pot, bids between 0.3 and 1.5 SOL
+    async function boundedAuction(): Promise<{ pool: Pool; members: Keypair[] }> {
+      const creator = await fundedKeypair();
+      const members = [creator, await fundedKeypair(), await fundedKeypair()];
+      const pool = await createSolPool(creator, {
+        maxParticipants: 3,
+        payoutMode: { auction: {} },
+        minDiscountBps: 1000,
+        maxDiscountBps: 5000,
+      });
+      for (const member of members.slice(1)) {
+        await joinSolPool(pool, member);
+      }
+      return { pool, members };
+    }
+
+    before(protocolReady);
+
+    it('rejects discount bounds that are inverted or take the whole pot', async () => {
+      const creator = await fundedKeypair();
+      const auction = { payoutMode: { auction: {} } };
+      await expectError(createSolPool(creator, { ...auction, minDiscountBps: 3000, maxDiscountBps: 2000 }), 'InvalidPoolConfig');
+      await expectError(createSolPool(creator, { ...auction, maxDiscountBps: 10000 }), 'InvalidPoolConfig');
+    });
+
+    it('rejects revealed bids outside the bounds', async () => {
+      const { pool, members } = await boundedAuction();
+      const [alice, bob, carol] = members;
+      const lowSalt = await commitBid(pool, alice, sol(0.29));
+      const maxSalt = await commitBid(pool, bob, sol(1.5));
+      const highSalt = await commitBid(pool, carol, sol(1.51));
+      await advanceCycle(pool);
+
+      await expectError(revealBid(pool, alice, sol(0.29), lowSalt), 'BidTooLow');
+      await expectError(revealBid(pool, carol, sol(1.51), highSalt), 'BidTooHigh');
+
+      await revealBid(pool, bob, sol(1.5), maxSalt);
+      assert.equal((await fetchGroup(pool)).currentWinner.toBase58(), bob.publicKey.toBase58());
+    });
+
+    it('closes the auction as soon as a maximum bid is revealed', async () => {
+      const { pool, members } = await boundedAuction();
+      const [alice, bob] = members;
+      await commitBid(pool, bob, sol(0.5));
+      const maxSalt = await commitBid(pool, alice, sol(1.5));
+      await advanceCycle(pool);
+
+      // Nothing still sealed can beat it, so the auction does not wait for the other reveal
+      await revealBid(pool, alice, sol(1.5), maxSalt);
+
+      const group = await fetchGroup(pool);
+      assert.equal(phaseOf(group), 'contributing');
+      assert.equal(group.currentWinner.toBase58(), alice.publicKey.toBase58());
+      assert.equal(group.currentBidAmount.toString(), sol(1.5).toString());
+    });
+
+    it('awards the first maximum bid revealed', async () => {
+      const { pool, members } = await boundedAuction();
+      const [alice, bob] = members;
+      const bobSalt = await commitBid(pool, bob, sol(1.5));
+      const aliceSalt = await commitBid(pool, alice, sol(1.5));
+      await advanceCycle(pool);
+
+      // Alice committed later but revealed first, the auction is over before Bob reveals
+      await revealBid(pool, alice, sol(1.5), aliceSalt);
+      await expectError(revealBid(pool, bob, sol(1.5), bobSalt), 'InvalidPhase');
+      assert.equal((await fetchGroup(pool)).currentWinner.toBase58(), alice.publicKey.toBase58());
+    });
+  });
+
+  describe('tie-break rules', () => {
+    const bid = new anchor.BN(LAMPORTS_PER_SOL / 2);
+
+    async function tiedAuction(tieBreakRule: object): Promise<{ pool: Pool; members: Keypair[] }> {
+      const creator = await fundedKeypair();
+      const members = [creator, await fundedKeypair(), await fundedKeypair()];
+      const pool = await createSolPool(creator, { maxParticipants: 3, payoutMode: { auction: {} }, tieBreakRule });
+      for (const member of members.slice(1)) {
+        await joinSolPool(pool, member);
+      }
+      return { pool, members };
+    }
+
+    // Both bidders commit and reveal the same amount, in the given order
+    async function tie(pool: Pool, bidders: Keypair[]) {
+      const salts = [];
+      for (const bidder of bidders) {
+        salts.push(await commitBid(pool, bidder, bid));
+      }
+      await advanceCycle(pool);
+      for (const [index, bidder] of bidders.entries()) {
+        await revealBid(pool, bidder, bid, salts[index]);
+      }
+    }
+
+    // The finalisation event records how many bids tied and the rule that settled them
+    async function biddingFinalized(signature: string) {
+      await connection.confirmTransaction(signature, 'confirmed');
+      const transaction = await connection.getTransaction(signature, { commitment: 'confirmed', maxSupportedTransactionVersion: 0 });
+      const parser = new anchor.EventParser(program.programId, program.coder);
+      const events = [...parser.parseLogs(transaction.meta.logMessages)];
+      return events.find((event) => event.name.toLowerCase() === 'biddingfinalized').data as any;
+    }
+
+    before(protocolReady);
+
+    it('settles ties for the earliest commitment', async () => {
+      const { pool, members } = await tiedAuction({ earliestBid: {} });
+      const [, second, third] = members;
+      await tie(pool, [third, second]);
+
+      const event = await biddingFinalized(await advanceCycle(pool));
+      assert.equal(event.winner.toBase58(), third.publicKey.toBase58());
+      assert.equal(event.tiedBids, 2);
+      assert.deepEqual(event.tieBreakRule, { earliestBid: {} });
+      assert.equal((await fetchGroup(pool)).currentWinner.toBase58(), third.publicKey.toBase58());
+    });
+
+    it('settles ties for the member who joined first', async () => {
+      const { pool, members } = await tiedAuction({ lowestJoinIndex: {} });
+      const [, second, third] = members;
+      // The later joiner commits first and still loses the tie
+      await tie(pool, [third, second]);
+
+      const event = await biddingFinalized(await advanceCycle(pool));
+      assert.equal(event.winner.toBase58(), second.publicKey.toBase58());
+      assert.equal(event.tiedBids, 2);
+    });
+
+    it('draws random tie-breaks from SlotHashes', async () => {
+      const { pool, members } = await tiedAuction({ random: {} });
+      const [, second, third] = members;
+      await tie(pool, [second, third]);
+
+      await expectError(advanceCycle(pool), 'MissingRandomnessSource');
+      const event = await biddingFinalized(await advanceCycle(pool, true));
+      assert.include([second.publicKey.toBase58(), third.publicKey.toBase58()], event.winner.toBase58());
+      assert.deepEqual(event.tieBreakRule, { random: {} });
+    });
+  });
+});